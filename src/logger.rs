@@ -2,6 +2,7 @@ use linked_hash_map::LinkedHashMap;
 use std::{
     collections::{BTreeMap, HashMap},
     ops::Range,
+    time::Instant,
 };
 use tracing::{Id, Level};
 use tracing_subscriber::Layer;
@@ -31,11 +32,29 @@ struct MapLoggerInner {
 
     last_query: Option<Query>,
     cur_string: Option<Arc<String>>,
+    cur_lines: Option<Arc<Vec<LogLine>>>,
+    show_all_fields: bool,
+    /// The most verbose level to print (None prints everything)
+    level_threshold: Option<Level>,
+    show_timestamps: bool,
+    /// When the current processing started, which timestamps are relative to
+    start_time: Option<Instant>,
 
     thread_spans: HashMap<usize, SpanId>,
     frame_spans: HashMap<(usize, usize), SpanId>,
     live_spans: HashMap<Id, SpanId>,
     next_span_id: SpanId,
+    /// Bumped whenever the logs (or how they're printed) change
+    changes: u64,
+}
+
+impl MapLoggerInner {
+    /// Throws away any cached log printout, since it's changed
+    fn invalidate(&mut self) {
+        self.cur_string = None;
+        self.cur_lines = None;
+        self.changes += 1;
+    }
 }
 
 #[derive(Default, Debug, Clone)]
@@ -56,11 +75,22 @@ enum EventEntry {
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 struct MessageEntry {
+    time: Instant,
     level: Level,
     fields: BTreeMap<String, String>,
     target: String,
 }
 
+/// One line of log output, for views that want to style each line
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    /// The level of the message this line is part of (None for span headers and notes)
+    pub level: Option<Level>,
+    pub text: String,
+    /// How deeply nested in spans this line is
+    pub depth: usize,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum Query {
     All,
@@ -85,7 +115,40 @@ impl MapLogger {
             log.sub_spans.remove(&id);
         }
         log.root_span.events.clear();
-        log.cur_string = None;
+        log.invalidate();
+        log.start_time = Some(Instant::now());
+    }
+
+    /// Whether to print every field of an event, and not just its message.
+    pub fn set_show_all_fields(&self, show_all_fields: bool) {
+        let mut log = self.state.lock().unwrap();
+        if log.show_all_fields != show_all_fields {
+            log.show_all_fields = show_all_fields;
+            log.invalidate();
+        }
+    }
+
+    /// Whether to prefix each message with the milliseconds since processing started.
+    pub fn set_show_timestamps(&self, show_timestamps: bool) {
+        let mut log = self.state.lock().unwrap();
+        if log.show_timestamps != show_timestamps {
+            log.show_timestamps = show_timestamps;
+            log.invalidate();
+        }
+    }
+
+    /// Only print messages at this level or more severe.
+    pub fn set_level_threshold(&self, level: Level) {
+        let mut log = self.state.lock().unwrap();
+        if log.level_threshold != Some(level) {
+            log.level_threshold = Some(level);
+            log.invalidate();
+        }
+    }
+
+    /// Changes whenever the printed logs would, for views that cache what they make of them
+    pub fn changes(&self) -> u64 {
+        self.state.lock().unwrap().changes
     }
 
     pub fn string_for_all(&self) -> Arc<String> {
@@ -134,22 +197,85 @@ impl MapLogger {
         }
     }
 
+    /// The same logs as the `string_for_*` methods, but line by line with their levels.
+    pub fn lines_for(
+        &self,
+        thread_idx: Option<usize>,
+        frame_idx: Option<usize>,
+    ) -> Arc<Vec<LogLine>> {
+        let query = {
+            let log = self.state.lock().unwrap();
+            match (thread_idx, frame_idx) {
+                (Some(t), Some(f)) => log
+                    .thread_spans
+                    .get(&t)
+                    .zip(log.frame_spans.get(&(t, f)))
+                    .map(|(&thread, &frame)| Query::Frame(thread, frame)),
+                (Some(t), None) => log
+                    .thread_spans
+                    .get(&t)
+                    .map(|&thread| Query::Thread(thread)),
+                _ => Some(Query::All),
+            }
+        };
+        if let Some(query) = query {
+            self.lines_query(query)
+        } else {
+            Arc::new(vec![LogLine {
+                level: None,
+                text: "whoops!".to_owned(),
+                depth: 0,
+            }])
+        }
+    }
+
     fn string_query(&self, query: Query) -> Arc<String> {
         use std::fmt::Write;
 
-        fn print_indent(output: &mut String, depth: usize) {
-            write!(output, "{:indent$}", "", indent = depth * 4).unwrap();
+        let lines = self.lines_query(query);
+        let mut log = self.state.lock().unwrap();
+        if Some(query) == log.last_query {
+            if let Some(string) = &log.cur_string {
+                return string.clone();
+            }
+        }
+
+        let mut output = String::new();
+        for line in lines.iter() {
+            writeln!(
+                output,
+                "{:indent$}{}",
+                "",
+                line.text,
+                indent = line.depth * 4
+            )
+            .unwrap();
         }
+        let result = Arc::new(output);
+        log.cur_string = Some(result.clone());
+        result
+    }
+
+    /// The lines of `string_query`, before they're indented and joined up.
+    fn lines_query(&self, query: Query) -> Arc<Vec<LogLine>> {
+        use std::fmt::Write;
+
         fn print_span_recursive(
-            output: &mut String,
+            output: &mut Vec<LogLine>,
             sub_spans: &LinkedHashMap<SpanId, SpanEntry>,
             depth: usize,
             span: &SpanEntry,
             range: Option<Range<usize>>,
+            show_all_fields: bool,
+            level_threshold: Option<Level>,
+            start_time: Option<Instant>,
         ) {
             if !span.name.is_empty() {
-                print_indent(output, depth);
-                writeln!(output, "[{} {:?}]", span.name, span.fields).unwrap();
+                output.push(LogLine {
+                    level: None,
+                    text: format!("[{} {:?}]", span.name, span.fields),
+                    depth,
+                });
             }
 
             let event_range = if let Some(range) = range {
@@ -160,10 +286,33 @@ impl MapLogger {
             for event in event_range {
                 match event {
                     EventEntry::Message(event) => {
+                        // Less severe levels are "bigger" in tracing
+                        if level_threshold.is_some_and(|threshold| event.level > threshold) {
+                            continue;
+                        }
                         if let Some(message) = event.fields.get("message") {
-                            print_indent(output, depth + 1);
-                            // writeln!(output, "[{:5}] {}", event.level, message).unwrap();
-                            writeln!(output, "{message}").unwrap();
+                            let mut text = String::new();
+                            if let Some(start_time) = start_time {
+                                let offset = event.time.saturating_duration_since(start_time);
+                                write!(text, "[+{:>6}ms] ", offset.as_millis()).unwrap();
+                            }
+                            write!(text, "[{:5}] {}", event.level, message).unwrap();
+                            output.push(LogLine {
+                                level: Some(event.level),
+                                text,
+                                depth: depth + 1,
+                            });
+                        }
+                        if show_all_fields {
+                            for (key, val) in &event.fields {
+                                if key != "message" {
+                                    output.push(LogLine {
+                                        level: Some(event.level),
+                                        text: format!("{key}: {val}"),
+                                        depth: depth + 2,
+                                    });
+                                }
+                            }
                         }
                     }
                     EventEntry::Span(sub_span) => {
@@ -173,6 +322,9 @@ impl MapLogger {
                             depth + 1,
                             &sub_spans[sub_span],
                             None,
+                            show_all_fields,
+                            level_threshold,
+                            start_time,
                         );
                     }
                 }
@@ -181,13 +333,19 @@ impl MapLogger {
 
         let mut log = self.state.lock().unwrap();
         if Some(query) == log.last_query {
-            if let Some(string) = &log.cur_string {
-                return string.clone();
+            if let Some(lines) = &log.cur_lines {
+                return lines.clone();
             }
         }
         log.last_query = Some(query);
+        log.cur_string = None;
 
-        let mut output = String::new();
+        let note = |text: &str| LogLine {
+            level: None,
+            text: text.to_owned(),
+            depth: 0,
+        };
+        let mut output = vec![note(&format!("{query:?}"))];
 
         let (span_to_print, range) = match query {
             Query::All => (&log.root_span, None),
@@ -236,36 +394,54 @@ impl MapLogger {
                 };
 
                 // Add a message indicating how to read this special snapshot
-                writeln!(
-                    &mut output,
-                    "Viewing logs for a frame's stackwalk, which has two parts"
-                )
-                .unwrap();
-                writeln!(
-                    &mut output,
-                    "  1. How the frame was computed (the stackwalk of its callee)"
-                )
-                .unwrap();
-                writeln!(
-                    &mut output,
-                    "  2. How the frame itself was walked (producing its caller)"
-                )
-                .unwrap();
-                writeln!(&mut output).unwrap();
+                output.push(note(
+                    "Viewing logs for a frame's stackwalk, which has two parts",
+                ));
+                output.push(note(
+                    "  1. How the frame was computed (the stackwalk of its callee)",
+                ));
+                output.push(note(
+                    "  2. How the frame itself was walked (producing its caller)",
+                ));
+                output.push(note(""));
 
                 (thread_span, Some(range_start..range_end))
             }
         };
 
-        print_span_recursive(&mut output, &log.sub_spans, 0, span_to_print, range);
+        print_span_recursive(
+            &mut output,
+            &log.sub_spans,
+            0,
+            span_to_print,
+            range,
+            log.show_all_fields,
+            log.level_threshold,
+            log.show_timestamps.then_some(log.start_time).flatten(),
+        );
 
-        output = format!("{query:?}\n{output}");
         let result = Arc::new(output);
-        log.cur_string = Some(result.clone());
+        log.cur_lines = Some(result.clone());
         result
     }
 }
 
+/// The byte ranges of every (case-insensitive) match of `query` in `text`,
+/// for searching the output of the `string_for_*` methods.
+pub fn search_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    // Lowercasing can change byte lengths for some non-ascii characters,
+    // so only fold ascii to keep the offsets valid for the original text.
+    let haystack = text.to_ascii_lowercase();
+    let needle = query.to_ascii_lowercase();
+    haystack
+        .match_indices(&needle)
+        .map(|(start, found)| start..start + found.len())
+        .collect()
+}
+
 impl<S> Layer<S> for MapLogger
 where
     S: tracing::Subscriber,
@@ -278,7 +454,7 @@ where
         }
         let mut log = self.state.lock().unwrap();
         // Invalidate any cached log printout
-        log.cur_string = None;
+        log.invalidate();
 
         // Grab the parent span (or the dummy root span)
         let cur_span = if let Some(span) = ctx.event_span(event) {
@@ -295,6 +471,7 @@ where
 
         // Store the message in the span
         cur_span.events.push(EventEntry::Message(MessageEntry {
+            time: Instant::now(),
             level: *event.metadata().level(),
             fields,
             target: target.to_owned(),
@@ -313,7 +490,7 @@ where
         }
         let mut log = self.state.lock().unwrap();
         // Invalidate any cache log printout
-        log.cur_string = None;
+        log.invalidate();
 
         // Create a new persistent id for this span, `tracing` may recycle its ids
         let new_span_id = log.next_span_id;
@@ -2,7 +2,7 @@
 
 use clap::Parser;
 use eframe::egui;
-use egui::{Color32, Ui, Vec2};
+use egui::{RichText, Ui, Vec2};
 use egui_extras::{Size, TableBuilder};
 use logger::MapLogger;
 use memmap2::Mmap;
@@ -12,11 +12,13 @@ use minidump_processor::ProcessState;
 use minidump_unwind::{CallStack, StackFrame};
 use processor::{
     MaybeMinidump, MaybeProcessed, MinidumpAnalysis, ProcessDump, ProcessingStatus, ProcessorTask,
+    SymbolPriority,
 };
+use saved_settings::SavedSettings;
 use std::{
     cmp::Ordering,
     path::PathBuf,
-    sync::{Arc, Condvar, Mutex},
+    sync::{atomic, Arc, Condvar, Mutex},
 };
 use tracing_subscriber::prelude::*;
 use ui_logs::LogUiState;
@@ -25,15 +27,39 @@ use ui_raw_dump::RawDumpUiState;
 
 pub mod logger;
 pub mod processor;
+mod result_cache;
+mod saved_settings;
+mod ui_compare;
+mod ui_disasm;
 mod ui_logs;
 mod ui_processed;
 mod ui_raw_dump;
 mod ui_settings;
 
 struct MyApp {
-    logger: MapLogger,
     settings: Settings,
+    /// The document currently being looked at
+    doc: Document,
+    /// All the other open documents
+    other_docs: Vec<Document>,
+    next_doc_id: usize,
+    /// The document (id) the current one is compared against in the compare tab
+    compare_with: Option<usize>,
+    /// What's currently on disk, to know when the settings need saving
+    saved_settings: SavedSettings,
+    /// Why the last dropped file wasn't opened
+    drop_error: Option<String>,
+    /// Whether a default tab of compare has already been switched to
+    compared_by_default: bool,
+}
+
+/// One open minidump, with its own processor thread, logs, and view state
+struct Document {
+    /// Unique for the life of the app, and increasing in the order documents were opened
+    id: usize,
+    path: Option<PathBuf>,
     tab: Tab,
+    logger: MapLogger,
     raw_dump_ui_state: RawDumpUiState,
     processed_ui_state: ProcessedUiState,
     log_ui_state: LogUiState,
@@ -42,10 +68,14 @@ struct MyApp {
     last_status: ProcessingStatus,
     minidump: MaybeMinidump,
     processed: MaybeProcessed,
+    /// Why processing failed, if it wasn't a plain read/process error
+    processing_error: Option<String>,
     pointer_width: PointerWidth,
 
     task_sender: Arc<(Mutex<Option<ProcessorTask>>, Condvar)>,
     analysis_state: Arc<MinidumpAnalysis>,
+    /// Sends tracing events about this document to its `logger`
+    dispatch: tracing::Dispatch,
 }
 
 struct Settings {
@@ -53,17 +83,61 @@ struct Settings {
     picked_path: Option<String>,
     symbol_paths: Vec<(String, bool)>,
     symbol_urls: Vec<(String, bool)>,
+    symbol_urls_from_cli: bool,
+    symbol_paths_from_cli: bool,
     symbol_cache: (String, bool),
+    /// (debug id, path) pairs of local symbol files to use for specific modules
+    symbol_overrides: Vec<(String, String)>,
     http_timeout_secs: String,
+    max_symbol_size_mb: String,
+    max_unwind_frames: String,
+    symbol_retries: String,
+    /// Threads for the async runtime that downloads symbols, empty for just the processor's
+    download_threads: String,
+    /// Processing a dump the same way as before reuses the result cached on disk
+    reuse_results: bool,
+    allow_stack_scan: bool,
     raw_dump_brief: bool,
+    /// Comma-separated bits of environment variable names whose values get hidden
+    sensitive_env_patterns: String,
+    focus_crash_on_load: bool,
+    symbol_priority: SymbolPriority,
+    /// The tab to switch to once it's available for a newly opened dump. Compare
+    /// shows the processed tab until there's a second processed dump to compare with.
+    default_tab: Tab,
+    full_module_paths: bool,
+    /// Show `_ZN...`-style function names the way they were written in the source
+    demangle_names: bool,
+    /// Walk the stacks without looking up any symbols, for a quick first look
+    skip_symbols: bool,
+    /// Never fetch symbols from the network
+    offline: bool,
+    theme: Theme,
+    /// Multiplies the system's scale factor, to make everything bigger or smaller
+    ui_scale: f32,
+    /// Where to look for the source files named in the symbols, empty for nowhere
+    source_root: String,
+    /// How to open a source file at a line, with `{path}` and `{line}` placeholders.
+    /// Empty to just open the file with whatever the OS likes.
+    editor_url: String,
+    /// Recently opened minidumps, most recent first
+    recent_paths: Vec<PathBuf>,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+enum Theme {
+    Dark,
+    Light,
+    FollowSystem,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
 enum Tab {
     Settings,
     Processed,
     RawDump,
     Logs,
+    Compare,
 }
 
 #[derive(Parser)]
@@ -72,94 +146,148 @@ struct Cli {
     symbols_url: Vec<String>,
     #[clap(action, long)]
     symbols_path: Vec<String>,
+    #[clap(value_enum, long, default_value = "processed")]
+    tab: Tab,
+    /// Don't open a window, just print a report for the first minidump and exit
+    #[clap(action, long)]
+    headless: bool,
     #[clap(action)]
     minidumps: Vec<PathBuf>,
 }
 
 const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 1000;
+const MAX_RECENT_PATHS: usize = 10;
+const DEFAULT_SENSITIVE_ENV_PATTERNS: &str = "TOKEN,SECRET,KEY,PASSWORD,PASSWD,CREDENTIAL,AUTH";
+
+/// Mirrors `Settings::demangle_names`. Function names get printed from all over
+/// (including headless runs), so this is simpler than threading settings everywhere.
+static DEMANGLE_NAMES: atomic::AtomicBool = atomic::AtomicBool::new(true);
+
+fn default_symbol_urls() -> Vec<(String, bool)> {
+    vec![
+        ("https://symbols.mozilla.org/".to_string(), true),
+        (
+            "https://msdl.microsoft.com/download/symbols/".to_string(),
+            true,
+        ),
+        (String::new(), true),
+    ]
+}
 
 fn main() {
     let cli = Cli::parse();
+
+    // Each document has its own logger (see `Document::new`). The global subscriber
+    // makes sure events from the `log` crate get forwarded to those, and prints
+    // anything that isn't about a particular document (like failing to save settings).
+    // That's only warnings and up unless RUST_LOG says otherwise, the UI libraries
+    // have a lot to say otherwise.
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn"));
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_filter(filter),
+        )
+        .init();
+
+    // Anything given on the command line wins over the saved settings
+    let saved_settings = SavedSettings::load();
     let available_paths = cli.minidumps;
-    let symbol_paths = if cli.symbols_path.is_empty() {
-        vec![(String::new(), true)]
-    } else {
+    let default_tab = cli.tab;
+    let headless = cli.headless;
+    let symbol_paths_from_cli = !cli.symbols_path.is_empty();
+    let symbol_paths = if symbol_paths_from_cli {
         cli.symbols_path.into_iter().map(|p| (p, true)).collect()
-    };
-    let symbol_urls = if cli.symbols_url.is_empty() {
-        vec![
-            ("https://symbols.mozilla.org/".to_string(), true),
-            (
-                "https://msdl.microsoft.com/download/symbols/".to_string(),
-                true,
-            ),
-            (String::new(), true),
-        ]
     } else {
+        saved_settings
+            .symbol_paths
+            .clone()
+            .unwrap_or_else(|| vec![(String::new(), true)])
+    };
+    let symbol_urls_from_cli = !cli.symbols_url.is_empty();
+    let symbol_urls = if symbol_urls_from_cli {
         cli.symbols_url.into_iter().map(|p| (p, true)).collect()
+    } else {
+        saved_settings
+            .symbol_urls
+            .clone()
+            .unwrap_or_else(default_symbol_urls)
     };
+    let symbol_cache = saved_settings.symbol_cache.clone().unwrap_or_else(|| {
+        (
+            std::env::temp_dir()
+                .join("minidump-cache")
+                .to_string_lossy()
+                .into_owned(),
+            true,
+        )
+    });
+    let http_timeout_secs = saved_settings
+        .http_timeout_secs
+        .clone()
+        .unwrap_or_else(|| DEFAULT_HTTP_TIMEOUT_SECS.to_string());
 
-    let logger = MapLogger::new();
+    let settings = Settings {
+        available_paths,
+        picked_path: None,
+        raw_dump_brief: saved_settings.raw_dump_brief.unwrap_or(true),
+        sensitive_env_patterns: saved_settings
+            .sensitive_env_patterns
+            .clone()
+            .unwrap_or_else(|| DEFAULT_SENSITIVE_ENV_PATTERNS.to_owned()),
+        focus_crash_on_load: true,
+        symbol_priority: SymbolPriority::LocalFirst,
+        default_tab,
+        full_module_paths: false,
+        demangle_names: saved_settings.demangle_names.unwrap_or(true),
+        skip_symbols: false,
+        offline: false,
+        theme: saved_settings.theme.unwrap_or(Theme::FollowSystem),
+        ui_scale: saved_settings.ui_scale.unwrap_or(1.0).clamp(0.75, 2.0),
+        source_root: saved_settings.source_root.clone().unwrap_or_default(),
+        editor_url: saved_settings.editor_url.clone().unwrap_or_default(),
+        recent_paths: saved_settings.recent_paths.clone(),
+        symbol_urls,
+        symbol_urls_from_cli,
+        symbol_paths,
+        symbol_paths_from_cli,
+        symbol_cache,
+        symbol_overrides: saved_settings.symbol_overrides.clone().unwrap_or_default(),
+        http_timeout_secs,
+        max_symbol_size_mb: String::new(),
+        max_unwind_frames: String::new(),
+        symbol_retries: String::new(),
+        download_threads: String::new(),
+        reuse_results: false,
+        allow_stack_scan: true,
+    };
 
-    tracing_subscriber::registry().with(logger.clone()).init();
+    DEMANGLE_NAMES.store(settings.demangle_names, atomic::Ordering::Relaxed);
+    if headless {
+        std::process::exit(run_headless(&settings));
+    }
 
     let options = eframe::NativeOptions {
         drag_and_drop_support: true,
         initial_window_size: Some(Vec2::new(1000.0, 800.0)),
         ..Default::default()
     };
-    let task_sender = Arc::new((Mutex::new(None::<ProcessorTask>), Condvar::new()));
-    let task_receiver = task_sender.clone();
-    let analysis_receiver = Arc::new(MinidumpAnalysis::default());
-    let analysis_sender = analysis_receiver.clone();
-    let logger_handle = logger.clone();
-
-    // Start the processor background thread
-    let _handle = std::thread::spawn(move || {
-        processor::run_processor(task_receiver, analysis_sender, logger_handle);
-    });
-
     // Launch the app
     eframe::run_native(
         "rust-minidump debugger",
         options,
         Box::new(|_cc| {
             Box::new(MyApp {
-                logger,
-                tab: Tab::Settings,
-                settings: Settings {
-                    available_paths,
-                    picked_path: None,
-                    raw_dump_brief: true,
-                    symbol_urls,
-                    symbol_paths,
-                    symbol_cache: (
-                        std::env::temp_dir()
-                            .join("minidump-cache")
-                            .to_string_lossy()
-                            .into_owned(),
-                        true,
-                    ),
-                    http_timeout_secs: DEFAULT_HTTP_TIMEOUT_SECS.to_string(),
-                },
-                raw_dump_ui_state: RawDumpUiState { cur_stream: 0 },
-                processed_ui_state: ProcessedUiState {
-                    cur_thread: 0,
-                    cur_frame: 0,
-                },
-                log_ui_state: LogUiState {
-                    cur_thread: None,
-                    cur_frame: None,
-                },
-
-                cur_status: ProcessingStatus::NoDump,
-                last_status: ProcessingStatus::NoDump,
-                minidump: None,
-                processed: None,
-                pointer_width: PointerWidth::Unknown,
-
-                task_sender,
-                analysis_state: analysis_receiver,
+                settings,
+                doc: Document::new(0),
+                other_docs: vec![],
+                next_doc_id: 1,
+                compare_with: None,
+                saved_settings,
+                drop_error: None,
+                compared_by_default: false,
             })
         }),
     );
@@ -167,21 +295,306 @@ fn main() {
 
 // The main even loop
 impl eframe::App for MyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         self.poll_processor_state();
-        self.update_ui(ctx);
-        self.last_status = self.cur_status;
+        // Table column widths and such are all in points, so they scale along with this
+        let pixels_per_point =
+            frame.info().native_pixels_per_point.unwrap_or(1.0) * self.settings.ui_scale;
+        if (ctx.pixels_per_point() - pixels_per_point).abs() > 0.001 {
+            ctx.set_pixels_per_point(pixels_per_point);
+        }
+        // The UI logs to the global subscriber, only what's done to a document (polling
+        // and processing it) goes to that document's logs
+        self.update_ui(ctx, frame.info().prefer_dark_mode);
+        self.save_settings_if_changed();
+        self.doc.last_status = self.doc.cur_status;
     }
 }
 
 // Core State Updating
 impl MyApp {
+    fn save_settings_if_changed(&mut self) {
+        let mut saved_settings = self.saved_settings.clone();
+        saved_settings.update_from(&self.settings);
+        if saved_settings != self.saved_settings {
+            saved_settings.save();
+            self.saved_settings = saved_settings;
+        }
+    }
+
+    fn open_file_dialog(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("minidump", &["dmp", "gz", "zst"])
+            .pick_file()
+        {
+            self.settings.available_paths.push(path);
+            self.set_path(self.settings.available_paths.len() - 1);
+        }
+    }
+
+    /// Opens one of the available paths, in a new document unless the current one
+    /// is still empty. If it's already open, just switch to it.
+    fn set_path(&mut self, idx: usize) {
+        let path = self.settings.available_paths[idx].clone();
+        let canonical_path = path.canonicalize().unwrap_or_else(|_| path.clone());
+        let recent_paths = &mut self.settings.recent_paths;
+        recent_paths.retain(|recent| *recent != canonical_path);
+        recent_paths.insert(0, canonical_path);
+        recent_paths.truncate(MAX_RECENT_PATHS);
+        self.settings.picked_path = Some(path.display().to_string());
+
+        if let Some(id) = self
+            .all_docs()
+            .find(|doc| doc.path.as_ref() == Some(&path))
+            .map(|doc| doc.id)
+        {
+            self.switch_doc(id);
+            return;
+        }
+        if self.doc.path.is_some() {
+            let doc = Document::new(self.next_doc_id);
+            self.next_doc_id += 1;
+            self.other_docs.push(std::mem::replace(&mut self.doc, doc));
+        }
+        self.doc.load(path);
+    }
+
+    /// Every open document, in the order they were opened
+    fn all_docs(&self) -> impl Iterator<Item = &Document> {
+        let mut docs = std::iter::once(&self.doc)
+            .chain(&self.other_docs)
+            .collect::<Vec<_>>();
+        docs.sort_by_key(|doc| doc.id);
+        docs.into_iter()
+    }
+
+    fn switch_doc(&mut self, id: usize) {
+        if let Some(idx) = self.other_docs.iter().position(|doc| doc.id == id) {
+            std::mem::swap(&mut self.doc, &mut self.other_docs[idx]);
+        }
+    }
+
+    fn close_doc(&mut self, id: usize) {
+        if self.doc.id == id {
+            // Switch to the neighbouring document, or a fresh one if this was the last
+            let next = self
+                .all_docs()
+                .map(|doc| doc.id)
+                .filter(|&other| other != id)
+                .min_by_key(|&other| other.abs_diff(id));
+            match next {
+                Some(next) => {
+                    self.switch_doc(next);
+                    self.other_docs.retain(|doc| doc.id != id);
+                }
+                None => {
+                    self.doc = Document::new(self.next_doc_id);
+                    self.next_doc_id += 1;
+                }
+            }
+        } else {
+            self.other_docs.retain(|doc| doc.id != id);
+        }
+    }
+
     fn poll_processor_state(&mut self) {
+        for doc in std::iter::once(&mut self.doc).chain(&mut self.other_docs) {
+            let dispatch = doc.dispatch.clone();
+            tracing::dispatcher::with_default(&dispatch, || doc.poll(&self.settings));
+        }
+        if self.settings.default_tab == Tab::Compare
+            && !self.compared_by_default
+            && self.doc.tab == Tab::Processed
+            && self.can_compare()
+        {
+            self.doc.tab = Tab::Compare;
+            self.compared_by_default = true;
+        }
+    }
+
+    /// Whether the current document and another one both have processed results
+    fn can_compare(&self) -> bool {
+        matches!(self.doc.processed, Some(Ok(_)))
+            && self
+                .other_docs
+                .iter()
+                .any(|doc| matches!(doc.processed, Some(Ok(_))))
+    }
+
+    fn process_dump(&mut self, dump: Arc<Minidump<'static, Mmap>>) {
+        let dispatch = self.doc.dispatch.clone();
+        tracing::dispatcher::with_default(&dispatch, || {
+            self.doc.process_dump(&self.settings, dump)
+        });
+    }
+
+    fn cancel_processing(&mut self) {
+        self.doc.cancel_processing();
+    }
+
+    fn stop_fetching_symbols(&self) {
+        tracing::dispatcher::with_default(&self.doc.dispatch, || self.doc.stop_fetching_symbols());
+    }
+}
+
+impl Document {
+    fn new(id: usize) -> Self {
+        let logger = MapLogger::new();
+        let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(logger.clone()));
+        let task_sender = Arc::new((Mutex::new(None::<ProcessorTask>), Condvar::new()));
+        let task_receiver = task_sender.clone();
+        let analysis_state = Arc::new(MinidumpAnalysis::default());
+        let analysis_sender = analysis_state.clone();
+        let logger_handle = logger.clone();
+        let thread_dispatch = dispatch.clone();
+
+        // Start the processor background thread, logging to this document's logger
+        let _handle = std::thread::spawn(move || {
+            tracing::dispatcher::with_default(&thread_dispatch, || {
+                processor::run_processor(task_receiver, analysis_sender, logger_handle);
+            });
+        });
+
+        Self {
+            id,
+            path: None,
+            tab: Tab::Settings,
+            logger,
+            raw_dump_ui_state: RawDumpUiState {
+                cur_stream: 0,
+                show_raw_linux_streams: false,
+                show_raw_crashpad_info: false,
+                show_raw_mac_crash_info: false,
+                redact_environ: true,
+                cur_memory_region: None,
+                hex_offset: 0,
+                hex_jump: String::new(),
+                file_bytes: None,
+                maps_sort: ui_raw_dump::RegionSort::File,
+                maps_sort_descending: false,
+                memory_info_sort: ui_raw_dump::RegionSort::File,
+                memory_info_sort_descending: false,
+                highlight_module: None,
+                scroll_to_module: false,
+            },
+            processed_ui_state: ProcessedUiState {
+                cur_thread: 0,
+                cur_frame: 0,
+                show_return_address: false,
+                thread_id_query: String::new(),
+                backtrace_generation: 0,
+                search_query: String::new(),
+                show_logs_panel: false,
+                show_disasm_panel: false,
+                address_query: String::new(),
+                expanded_signatures: Default::default(),
+                min_trust: minidump_unwind::FrameTrust::None,
+                only_missing_symbols: false,
+                selected_frames: Default::default(),
+                json_error: None,
+                thread_filter: String::new(),
+                show_all_threads: false,
+            },
+            log_ui_state: LogUiState {
+                cur_thread: None,
+                cur_frame: None,
+                diff_next_frame: false,
+                thread_id_query: String::new(),
+                show_all_fields: false,
+                search: Default::default(),
+                level_threshold: tracing::Level::TRACE,
+                show_timestamps: false,
+                rich: false,
+                scroll_offsets: Default::default(),
+                shown_logs: None,
+                selected_lines: Default::default(),
+                frame_diff: None,
+            },
+
+            cur_status: ProcessingStatus::NoDump,
+            last_status: ProcessingStatus::NoDump,
+            minidump: None,
+            processed: None,
+            processing_error: None,
+            pointer_width: PointerWidth::Unknown,
+            task_sender,
+            analysis_state,
+            dispatch,
+        }
+    }
+
+    fn name(&self) -> String {
+        match &self.path {
+            Some(path) => path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            None => "(no minidump)".to_owned(),
+        }
+    }
+
+    /// The OS and CPU the dump came from, to keep in mind when reading addresses
+    fn arch_summary(&self) -> Option<String> {
+        use minidump::system_info::Cpu;
+        let (os, cpu) = match (&self.processed, &self.minidump) {
+            (Some(Ok(state)), _) => (state.system_info.os, state.system_info.cpu),
+            (_, Some(Ok(dump))) => {
+                let info = dump.get_stream::<minidump::MinidumpSystemInfo>().ok()?;
+                (info.os, info.cpu)
+            }
+            _ => return None,
+        };
+        let bits = match cpu.pointer_width() {
+            PointerWidth::Bits32 => "32-bit",
+            PointerWidth::Bits64 => "64-bit",
+            _ => "unknown width",
+        };
+        let endianness = match cpu {
+            Cpu::Ppc | Cpu::Ppc64 | Cpu::Sparc => "big-endian",
+            Cpu::Unknown(_) => "unknown endianness",
+            _ => "little-endian",
+        };
+        Some(format!("{os} · {cpu} · {bits} · {endianness}"))
+    }
+
+    /// Whether the dump is still being read or processed
+    fn is_busy(&self) -> bool {
+        matches!(
+            self.cur_status,
+            ProcessingStatus::ReadingDump
+                | ProcessingStatus::RawProcessing
+                | ProcessingStatus::Symbolicating
+        )
+    }
+
+    fn load(&mut self, path: PathBuf) {
+        self.cur_status = ProcessingStatus::ReadingDump;
+        let (lock, condvar) = &*self.task_sender;
+        let mut new_task = lock.lock().unwrap();
+        *new_task = Some(ProcessorTask::ReadDump(path.clone()));
+        self.path = Some(path);
+        self.minidump = None;
+        self.processed = None;
+        self.processing_error = None;
+        self.raw_dump_ui_state.file_bytes = None;
+        self.tab = Tab::Settings;
+        condvar.notify_one();
+    }
+
+    fn poll(&mut self, settings: &Settings) {
         // Fetch updates from processing thread
         let new_minidump = self.analysis_state.minidump.lock().unwrap().take();
         if let Some(dump) = new_minidump {
             if let Ok(dump) = &dump {
-                self.process_dump(dump.clone());
+                if self.tab == Tab::Settings
+                    && matches!(settings.default_tab, Tab::RawDump | Tab::Logs)
+                {
+                    self.tab = settings.default_tab;
+                }
+                self.process_dump(settings, dump.clone());
+            } else {
+                self.cur_status = ProcessingStatus::Failed;
             }
             self.minidump = Some(dump);
         }
@@ -191,7 +604,10 @@ impl MyApp {
             let partial = stats.processor_stats.take_unwalked_result();
             if let Some(state) = partial {
                 self.pointer_width = state.system_info.cpu.pointer_width();
-                if self.tab == Tab::Settings && self.cur_status <= ProcessingStatus::RawProcessing {
+                if self.tab == Tab::Settings
+                    && matches!(settings.default_tab, Tab::Processed | Tab::Compare)
+                    && self.cur_status <= ProcessingStatus::RawProcessing
+                {
                     self.tab = Tab::Processed;
                 }
                 self.cur_status = ProcessingStatus::Symbolicating;
@@ -199,13 +615,24 @@ impl MyApp {
                 if let Some(crashed_thread) = state.requesting_thread {
                     self.processed_ui_state.cur_thread = crashed_thread;
                 }
+                self.focus_crash(settings);
                 self.processed = Some(Ok(Arc::new(state)));
             }
 
             if let Some(partial) = self.processed.as_mut().and_then(|p| p.as_mut().ok()) {
                 let partial = Arc::make_mut(partial);
+                let max_frames = stats.max_frames.unwrap_or(usize::MAX);
+                let allow_stack_scan = stats.allow_stack_scan;
                 stats.processor_stats.drain_new_frames(|frame| {
                     let thread = &mut partial.threads[frame.thread_idx];
+                    // Drop anything past the frame limits. Once a stack has been cut off
+                    // by a scanned frame, everything after it goes too.
+                    let cut_off = !allow_stack_scan
+                        && (processor::is_scanned(&frame.frame)
+                            || thread.frames.len() < frame.frame_idx);
+                    if frame.frame_idx >= max_frames || cut_off {
+                        return;
+                    }
                     match thread.frames.len().cmp(&frame.frame_idx) {
                         Ordering::Greater => {
                             // Allows us to overwrite the old context frame
@@ -220,72 +647,72 @@ impl MyApp {
                     }
                 });
             }
+            // The processor won't send a result for a walk it stopped, what's streamed
+            // in (and cut down to the limits above) is all there is
+            if stats.walk_cut_short && self.processed.is_some() {
+                self.cur_status = ProcessingStatus::Done;
+            }
         }
 
         let new_processed = self.analysis_state.processed.lock().unwrap().take();
         if let Some(processed) = new_processed {
-            if self.tab == Tab::Settings && self.cur_status <= ProcessingStatus::RawProcessing {
+            let first_result = self.cur_status <= ProcessingStatus::RawProcessing;
+            if self.tab == Tab::Settings
+                && matches!(settings.default_tab, Tab::Processed | Tab::Compare)
+                && first_result
+            {
                 self.tab = Tab::Processed;
             }
-            self.cur_status = ProcessingStatus::Done;
+            self.cur_status = if processed.is_ok() {
+                ProcessingStatus::Done
+            } else {
+                ProcessingStatus::Failed
+            };
             if let Ok(state) = &processed {
                 self.pointer_width = state.system_info.cpu.pointer_width();
                 if let Some(crashed_thread) = state.requesting_thread {
                     self.processed_ui_state.cur_thread = crashed_thread;
                 }
+                if first_result {
+                    self.focus_crash(settings);
+                }
             }
             self.processed = Some(processed);
         }
+
+        let new_error = self.analysis_state.error.lock().unwrap().take();
+        if let Some(error) = new_error {
+            self.cur_status = ProcessingStatus::Failed;
+            self.processing_error = Some(error);
+        }
     }
 
-    fn set_path(&mut self, idx: usize) {
-        let path = self.settings.available_paths[idx].clone();
-        self.cur_status = ProcessingStatus::ReadingDump;
-        self.settings.picked_path = Some(path.display().to_string());
-        let (lock, condvar) = &*self.task_sender;
-        let mut new_task = lock.lock().unwrap();
-        *new_task = Some(ProcessorTask::ReadDump(path));
-        self.minidump = None;
-        self.processed = None;
-        self.tab = Tab::Settings;
-        condvar.notify_one();
+    /// Select the top frame of the (already selected) crashing thread and
+    /// scroll the backtrace back to the top, if the user wants that.
+    fn focus_crash(&mut self, settings: &Settings) {
+        if settings.focus_crash_on_load {
+            self.processed_ui_state.cur_frame = 0;
+            self.processed_ui_state.backtrace_generation += 1;
+        }
     }
 
-    fn process_dump(&mut self, dump: Arc<Minidump<'static, Mmap>>) {
+    fn process_dump(&mut self, settings: &Settings, dump: Arc<Minidump<'static, Mmap>>) {
         let (lock, condvar) = &*self.task_sender;
         let mut new_task = lock.lock().unwrap();
         self.cur_status = ProcessingStatus::RawProcessing;
 
-        let symbol_paths = self
-            .settings
-            .symbol_paths
-            .iter()
-            .filter(|(path, enabled)| *enabled && !path.trim().is_empty())
-            .map(|(path, _enabled)| PathBuf::from(path))
-            .collect();
-        let symbol_urls = self
-            .settings
-            .symbol_urls
-            .iter()
-            .filter(|(url, enabled)| *enabled && !url.trim().is_empty())
-            .map(|(url, _enabled)| url.to_owned())
-            .collect();
-        let (raw_cache, cache_enabled) = &self.settings.symbol_cache;
-        let clear_cache = !cache_enabled;
-        let symbol_cache = PathBuf::from(raw_cache);
-        let http_timeout_secs = self
-            .settings
-            .http_timeout_secs
-            .parse::<u64>()
-            .unwrap_or(DEFAULT_HTTP_TIMEOUT_SECS);
-        *new_task = Some(ProcessorTask::ProcessDump(ProcessDump {
-            dump,
-            symbol_paths,
-            symbol_urls,
-            symbol_cache,
-            clear_cache,
-            http_timeout_secs,
-        }));
+        // If we're superseding an in-flight run, throw away everything it produced.
+        // The processor won't publish any more results for it once it sees our new
+        // task, and it reports stats into the old stats object it already grabbed.
+        self.processed = None;
+        self.processing_error = None;
+        *self.analysis_state.processed.lock().unwrap() = None;
+        *self.analysis_state.error.lock().unwrap() = None;
+        *self.analysis_state.stats.lock().unwrap() = Default::default();
+
+        *new_task = Some(ProcessorTask::ProcessDump(process_dump_task(
+            settings, dump,
+        )));
         condvar.notify_one();
     }
 
@@ -295,6 +722,140 @@ impl MyApp {
         *new_task = Some(ProcessorTask::Cancel);
         condvar.notify_one();
     }
+
+    /// Unlike cancelling, this keeps processing going, just without any more downloads.
+    fn stop_fetching_symbols(&self) {
+        let stats = self.analysis_state.stats.lock().unwrap();
+        stats.stop_fetching.store(true, atomic::Ordering::Relaxed);
+        tracing::info!("stopped fetching symbols, only using local ones from now on");
+    }
+}
+
+impl Drop for Document {
+    fn drop(&mut self) {
+        // Let the processor thread know it can go away
+        let (lock, condvar) = &*self.task_sender;
+        *lock.lock().unwrap() = Some(ProcessorTask::Quit);
+        condvar.notify_one();
+    }
+}
+
+/// What to tell the processor to do with a dump, according to the settings
+fn process_dump_task(settings: &Settings, dump: Arc<Minidump<'static, Mmap>>) -> ProcessDump {
+    let symbol_paths = settings
+        .symbol_paths
+        .iter()
+        .filter(|(path, enabled)| *enabled && !path.trim().is_empty())
+        .map(|(path, _enabled)| PathBuf::from(path))
+        .collect();
+    let symbol_urls = settings
+        .symbol_urls
+        .iter()
+        .filter(|_| !settings.offline)
+        .filter(|(url, enabled)| *enabled && !url.trim().is_empty())
+        .filter(|(url, _enabled)| {
+            if let Some(problem) = symbol_url_problem(url) {
+                tracing::warn!("skipping symbol server {url}: {problem}");
+                false
+            } else {
+                true
+            }
+        })
+        .map(|(url, _enabled)| url.to_owned())
+        .collect();
+    // Debug ids get compared uppercased, the way breakpad prints them
+    let symbol_overrides = settings
+        .symbol_overrides
+        .iter()
+        .filter(|(debug_id, path)| !debug_id.trim().is_empty() && !path.trim().is_empty())
+        .map(|(debug_id, path)| (debug_id.trim().to_uppercase(), PathBuf::from(path.trim())))
+        .collect();
+    let (raw_cache, cache_enabled) = &settings.symbol_cache;
+    let clear_cache = !cache_enabled;
+    let symbol_cache = PathBuf::from(raw_cache);
+    let http_timeout_secs = settings
+        .http_timeout_secs
+        .parse::<u64>()
+        .unwrap_or(DEFAULT_HTTP_TIMEOUT_SECS);
+    // An empty (or otherwise unparseable) limit means no limit, as does one too big to
+    // count in bytes
+    let max_symbol_bytes = settings
+        .max_symbol_size_mb
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|mb| mb.checked_mul(1024 * 1024));
+    let symbol_retries = settings.symbol_retries.trim().parse::<u32>().unwrap_or(0);
+    let max_frames = settings.max_unwind_frames.trim().parse::<usize>().ok();
+    let worker_threads = settings
+        .download_threads
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|&threads| threads > 0);
+    ProcessDump {
+        dump,
+        symbol_paths,
+        symbol_urls,
+        symbol_overrides,
+        symbol_cache,
+        clear_cache,
+        http_timeout_secs,
+        symbol_priority: settings.symbol_priority,
+        max_symbol_bytes,
+        symbol_retries,
+        worker_threads,
+        symbolicate: !settings.skip_symbols,
+        offline: settings.offline,
+        max_frames,
+        allow_stack_scan: settings.allow_stack_scan,
+        reuse_result: settings.reuse_results,
+    }
+}
+
+/// Processes the first minidump without any UI and prints a plain text report of
+/// it to stdout, for use in scripts. Returns the exit code.
+fn run_headless(settings: &Settings) -> i32 {
+    let Some(path) = settings.available_paths.first() else {
+        eprintln!("--headless needs a minidump to process");
+        return 2;
+    };
+    let dump = match processor::decompress_dump(path) {
+        Ok(Some(data)) => Minidump::read(data),
+        Ok(None) => Minidump::read_path(path),
+        Err(e) => {
+            eprintln!("failed to decompress {}: {e}", path.display());
+            return 1;
+        }
+    };
+    let dump = match dump {
+        Ok(dump) => Arc::new(dump),
+        Err(e) => {
+            eprintln!("failed to read {}: {e}", path.display());
+            return 1;
+        }
+    };
+    let task = process_dump_task(settings, dump);
+    // Nobody else can send tasks, so this only stops early for a runaway stack
+    let task_receiver = Arc::new((Mutex::new(None::<ProcessorTask>), Condvar::new()));
+    let analysis = Arc::new(MinidumpAnalysis::default());
+    match processor::process_minidump(&task_receiver, &analysis, &task, task.symbolicate) {
+        Some(Ok(state)) => {
+            print!("{}", ui_processed::crash_report(&state));
+            0
+        }
+        Some(Err(e)) => {
+            eprintln!("failed to process {}: {e}", path.display());
+            1
+        }
+        None => {
+            eprintln!(
+                "stack walk stopped after {} frames",
+                task.max_frames.unwrap_or_default()
+            );
+            1
+        }
+    }
 }
 
 // Main UI: sets up tabs and then shells out to the current view
@@ -303,47 +864,157 @@ impl MyApp {
 // because they don't care about eachother and things were getting way
 // out of control with all these unrelated UIs together!
 impl MyApp {
-    fn update_ui(&mut self, ctx: &egui::Context) {
+    fn update_ui(&mut self, ctx: &egui::Context, system_prefers_dark: Option<bool>) {
+        let dark = match self.settings.theme {
+            Theme::Dark => true,
+            Theme::Light => false,
+            // egui is dark by default, so stick with that if the system doesn't say
+            Theme::FollowSystem => system_prefers_dark.unwrap_or(true),
+        };
+        ctx.set_visuals(if dark {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        });
+        DEMANGLE_NAMES.store(self.settings.demangle_names, atomic::Ordering::Relaxed);
+
+        // Shortcuts, unless someone's typing in a text field
+        if !ctx.wants_keyboard_input() {
+            let (reprocess, open) = {
+                let input = ctx.input();
+                let command = input.modifiers.command;
+                (
+                    command && input.key_pressed(egui::Key::R),
+                    command && input.key_pressed(egui::Key::O),
+                )
+            };
+            if reprocess {
+                if let Some(Ok(dump)) = &self.doc.minidump {
+                    self.process_dump(dump.clone());
+                }
+            }
+            if open && !self.doc.is_busy() {
+                self.open_file_dialog();
+            }
+        }
+
+        if !self.other_docs.is_empty() {
+            egui::TopBottomPanel::top("document bar")
+                .resizable(false)
+                .show(ctx, |ui| self.ui_document_bar(ui));
+        }
         egui::TopBottomPanel::top("tab bar")
             .resizable(false)
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
-                    ui.selectable_value(&mut self.tab, Tab::Settings, "settings");
-                    if self.cur_status >= ProcessingStatus::RawProcessing {
-                        ui.selectable_value(&mut self.tab, Tab::RawDump, "raw dump");
+                    // If the dump couldn't even be read, there's nothing to look at
+                    let dump_ok = matches!(self.doc.minidump, Some(Ok(_)));
+                    ui.selectable_value(&mut self.doc.tab, Tab::Settings, "settings");
+                    if dump_ok && self.doc.cur_status >= ProcessingStatus::RawProcessing {
+                        ui.selectable_value(&mut self.doc.tab, Tab::RawDump, "raw dump");
+                    }
+                    if dump_ok && self.doc.cur_status >= ProcessingStatus::Symbolicating {
+                        ui.selectable_value(&mut self.doc.tab, Tab::Processed, "processed");
                     }
-                    if self.cur_status >= ProcessingStatus::Symbolicating {
-                        ui.selectable_value(&mut self.tab, Tab::Processed, "processed");
+                    if dump_ok && self.doc.cur_status >= ProcessingStatus::RawProcessing {
+                        ui.selectable_value(&mut self.doc.tab, Tab::Logs, "logs");
                     }
-                    if self.cur_status >= ProcessingStatus::RawProcessing {
-                        ui.selectable_value(&mut self.tab, Tab::Logs, "logs");
+                    if self.can_compare() {
+                        ui.selectable_value(&mut self.doc.tab, Tab::Compare, "compare");
+                    }
+                    if let Some(summary) = self.doc.arch_summary() {
+                        ui.with_layout(egui::Layout::right_to_left(), |ui| {
+                            ui.label(RichText::new(summary).strong());
+                        });
                     }
                 });
             });
-        egui::CentralPanel::default().show(ctx, |ui| match self.tab {
+        egui::CentralPanel::default().show(ctx, |ui| match self.doc.tab {
             Tab::Settings => self.ui_settings(ui, ctx),
             Tab::RawDump => self.ui_raw_dump(ui, ctx),
             Tab::Processed => self.ui_processed(ui, ctx),
             Tab::Logs => self.ui_logs(ui, ctx),
+            Tab::Compare => self.ui_compare(ui, ctx),
         });
     }
 
+    fn ui_document_bar(&mut self, ui: &mut Ui) {
+        let mut switch_to = None;
+        let mut close = None;
+        ui.horizontal(|ui| {
+            for doc in self.all_docs() {
+                let name = doc.name();
+                let selected = doc.id == self.doc.id;
+                let response = ui.selectable_label(selected, name);
+                let response = match &doc.path {
+                    Some(path) => response.on_hover_text(path.display().to_string()),
+                    None => response,
+                };
+                if response.clicked() {
+                    switch_to = Some(doc.id);
+                }
+                if ui.small_button("❌").on_hover_text("close").clicked() {
+                    close = Some(doc.id);
+                }
+                ui.separator();
+            }
+        });
+        if let Some(id) = switch_to {
+            self.switch_doc(id);
+        }
+        if let Some(id) = close {
+            self.close_doc(id);
+        }
+    }
+
     fn format_addr(&self, addr: u64) -> String {
-        match self.pointer_width {
-            minidump::system_info::PointerWidth::Bits32 => format!("0x{addr:08x}"),
-            minidump::system_info::PointerWidth::Bits64 => format!("0x{addr:016x}"),
-            minidump::system_info::PointerWidth::Unknown => format!("0x{addr:08x}"),
+        format_addr_for(self.doc.pointer_width, addr)
+    }
+
+    /// A table column just wide enough for the addresses `format_addr` makes
+    fn addr_column(&self, ui: &Ui, font: &egui::FontId) -> Size {
+        let digit_width = ui.fonts().glyph_width(font, '0');
+        let chars = self.format_addr(0).len() as f32;
+        let width = digit_width * chars + ui.spacing().item_spacing.x * 2.0;
+        Size::initial(width).at_least(width)
+    }
+
+    fn format_module_name(&self, name: &str) -> String {
+        if self.settings.full_module_paths {
+            name.to_owned()
+        } else {
+            basename(name).to_owned()
         }
     }
 }
 
+fn format_addr_for(pointer_width: PointerWidth, addr: u64) -> String {
+    match pointer_width {
+        minidump::system_info::PointerWidth::Bits32 => format!("0x{addr:08x}"),
+        minidump::system_info::PointerWidth::Bits64 => format!("0x{addr:016x}"),
+        minidump::system_info::PointerWidth::Unknown => format!("0x{addr:08x}"),
+    }
+}
+
 fn listing(
     ui: &mut Ui,
     ctx: &egui::Context,
     id: u64,
     items: impl IntoIterator<Item = (String, String)>,
+) {
+    let items = items.into_iter().map(|(lhs, rhs)| (lhs, rhs, None));
+    listing_with_hover(ui, ctx, id, items);
+}
+
+/// A `listing` where each value can have some hover text
+fn listing_with_hover(
+    ui: &mut Ui,
+    ctx: &egui::Context,
+    id: u64,
+    items: impl IntoIterator<Item = (String, String, Option<String>)>,
 ) {
     ui.push_id(id, |ui| {
+        let text_color = ui.visuals().text_color();
         let mono_font = egui::style::TextStyle::Monospace.resolve(ui.style());
         let body_font = egui::style::TextStyle::Body.resolve(ui.style());
         TableBuilder::new(ui)
@@ -358,11 +1029,11 @@ fn listing(
                 let widths = body.widths();
                 let col1_width = widths[0];
                 let col2_width = widths[1];
-                for (lhs, rhs) in items {
+                for (lhs, rhs, hover) in items {
                     let (col1, col2, row_height) = {
                         let fonts = ctx.fonts();
-                        let col1 = fonts.layout(lhs, body_font.clone(), Color32::BLACK, col1_width);
-                        let col2 = fonts.layout(rhs, mono_font.clone(), Color32::BLACK, col2_width);
+                        let col1 = fonts.layout(lhs, body_font.clone(), text_color, col1_width);
+                        let col2 = fonts.layout(rhs, mono_font.clone(), text_color, col2_width);
                         let row_height = col1.rect.height().max(col2.rect.height()) + 6.0;
                         (col1, col2, row_height)
                     };
@@ -371,7 +1042,12 @@ fn listing(
                             ui.label(col1);
                         });
                         row.col(|ui| {
-                            ui.label(col2);
+                            let response = ui.label(col2);
+                            if let Some(hover) = hover {
+                                response.on_hover_ui(|ui| {
+                                    ui.monospace(hover);
+                                });
+                            }
                         });
                     });
                 }
@@ -379,6 +1055,54 @@ fn listing(
     });
 }
 
+/// Why a symbol server URL won't work, if it won't
+fn symbol_url_problem(url: &str) -> Option<String> {
+    match url::Url::parse(url.trim()) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => None,
+        Ok(url) => Some(format!(
+            "unsupported scheme \"{}\" (expected http or https)",
+            url.scheme()
+        )),
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// Aligns two sequences by their longest common subsequence. Each entry of the
+/// result is a pair of indices into `old` and `new`: both are set for items that
+/// are in both, otherwise only one side is.
+fn diff_indices<T: PartialEq>(old: &[T], new: &[T]) -> Vec<(Option<usize>, Option<usize>)> {
+    // lcs[i][j] is the length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut output = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            output.push((Some(i), Some(j)));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            output.push((Some(i), None));
+            i += 1;
+        } else {
+            output.push((None, Some(j)));
+            j += 1;
+        }
+    }
+    output.extend((i..old.len()).map(|i| (Some(i), None)));
+    output.extend((j..new.len()).map(|j| (None, Some(j))));
+    output
+}
+
 fn threadname(stack: &CallStack) -> String {
     if let Some(name) = &stack.thread_name {
         format!("{} ({})", name, stack.thread_id)
@@ -387,6 +1111,21 @@ fn threadname(stack: &CallStack) -> String {
     }
 }
 
+/// Maps a user-entered thread_id (decimal or 0x-prefixed hex) to the index
+/// of that thread, which is what the UI and the logger use to refer to threads.
+fn thread_idx_for_id(state: &ProcessState, thread_id: &str) -> Option<usize> {
+    let thread_id = thread_id.trim();
+    let thread_id = if let Some(hex) = thread_id.strip_prefix("0x") {
+        u32::from_str_radix(hex, 16).ok()?
+    } else {
+        thread_id.parse::<u32>().ok()?
+    };
+    state
+        .threads
+        .iter()
+        .position(|stack| stack.thread_id == thread_id)
+}
+
 fn sourcename(file: &str) -> &str {
     let base = basename(file);
     match base.rsplit_once(':') {
@@ -457,6 +1196,42 @@ fn frame_signature_from_indices(
     buf
 }
 
+/// Whether symbols were loaded for the module while processing, or `None`
+/// if nothing on any stack needed them so they were never looked for
+fn module_has_symbols(state: &ProcessState, module: &minidump::MinidumpModule) -> Option<bool> {
+    state
+        .symbol_stats
+        .get(&module.name)
+        .map(|stats| stats.loaded_symbols)
+}
+
+/// Demangles Rust and C++ names (if that's turned on), leaving anything else alone.
+fn demangle(name: &str) -> std::borrow::Cow<'_, str> {
+    use std::borrow::Cow;
+    if !DEMANGLE_NAMES.load(atomic::Ordering::Relaxed) {
+        return Cow::Borrowed(name);
+    }
+    // Legacy Rust names are also valid C++ ones, but this does a nicer job of them
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+        return Cow::Owned(format!("{demangled:#}"));
+    }
+    // macOS sticks an extra underscore on the front
+    let cpp_name = if name.starts_with("__Z") {
+        &name[1..]
+    } else {
+        name
+    };
+    if cpp_name.starts_with("_Z") {
+        let demangled = cpp_demangle::Symbol::new(cpp_name)
+            .ok()
+            .and_then(|sym| sym.demangle(&Default::default()).ok());
+        if let Some(demangled) = demangled {
+            return Cow::Owned(demangled);
+        }
+    }
+    Cow::Borrowed(name)
+}
+
 fn frame_signature(
     f: &mut impl std::fmt::Write,
     frame: &StackFrame,
@@ -465,7 +1240,7 @@ fn frame_signature(
     if let Some(ref module) = frame.module {
         if let (Some(function), Some(_function_base)) = (&frame.function_name, &frame.function_base)
         {
-            write!(f, "{function}")?;
+            write!(f, "{}", demangle(function))?;
         } else {
             write!(
                 f,
@@ -1,14 +1,27 @@
 use std::{
-    path::PathBuf,
-    sync::{Arc, Condvar, Mutex},
+    collections::HashMap,
+    io::BufRead,
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc, Condvar, Mutex,
+    },
 };
 
+use async_trait::async_trait;
+use breakpad_symbols::{
+    breakpad_sym_lookup, FileError, FileKind, LocateSymbolsResult, SimpleSymbolSupplier,
+    SymbolError, SymbolFile, SymbolSupplier,
+};
 use memmap2::Mmap;
-use minidump::Minidump;
-use minidump_processor::{ProcessState, ProcessorOptions, PendingProcessorStatSubscriptions, PendingProcessorStats,};
+use minidump::{system_info::Cpu, Minidump, Module};
+use minidump_common::utils::basename;
+use minidump_processor::{
+    PendingProcessorStatSubscriptions, PendingProcessorStats, ProcessState, ProcessorOptions,
+};
 use minidump_unwind::{
-    http_symbol_supplier,
-    PendingSymbolStats,  Symbolizer,
+    http_symbol_supplier, FrameTrust, PendingSymbolStats, StackFrame, Symbolizer,
 };
 
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -19,10 +32,21 @@ pub enum ProcessingStatus {
     RawProcessing,
     Symbolicating,
     Done,
+    Failed,
+}
+
+/// Which symbols win when both a local symbol path and a symbol server have them.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SymbolPriority {
+    #[default]
+    LocalFirst,
+    ServerFirst,
 }
 
 pub enum ProcessorTask {
     Cancel,
+    /// The document this thread was processing is gone
+    Quit,
     ReadDump(PathBuf),
     ProcessDump(ProcessDump),
 }
@@ -35,12 +59,31 @@ pub struct MinidumpAnalysis {
     pub minidump: Arc<Mutex<MaybeMinidump>>,
     pub processed: Arc<Mutex<MaybeProcessed>>,
     pub stats: Arc<Mutex<ProcessingStats>>,
+    /// Set if processing died without producing any result (e.g. the processor panicked)
+    pub error: Arc<Mutex<Option<String>>>,
 }
 
 #[derive(Clone)]
 pub struct ProcessingStats {
     pub processor_stats: Arc<PendingProcessorStats>,
     pub pending_symbols: Arc<Mutex<PendingSymbolStats>>,
+    pub symbol_warnings: Arc<Mutex<Vec<String>>>,
+    /// Modules whose symbols are being looked up right now, oldest first
+    pub fetching_symbols: Arc<Mutex<Vec<String>>>,
+    /// Set to stop downloading symbols, finishing the walk with the ones we have on disk
+    pub stop_fetching: Arc<AtomicBool>,
+    /// Some module's symbols couldn't be found, even after retrying
+    pub symbol_lookup_failed: Arc<AtomicBool>,
+    /// This run was a plain stackwalk without any symbols
+    pub symbols_skipped: bool,
+    /// This run didn't touch the network
+    pub offline: bool,
+    /// The frame limits this run was started with, see [`ProcessDump`]
+    pub max_frames: Option<usize>,
+    pub allow_stack_scan: bool,
+    /// A stack ran past `max_frames` and the walk was stopped, so the stacks streamed
+    /// in so far are the whole result
+    pub walk_cut_short: bool,
 }
 
 impl Default for ProcessingStats {
@@ -54,6 +97,15 @@ impl Default for ProcessingStats {
         Self {
             processor_stats: Arc::new(PendingProcessorStats::new(subscriptions)),
             pending_symbols: Default::default(),
+            symbol_warnings: Default::default(),
+            fetching_symbols: Default::default(),
+            stop_fetching: Default::default(),
+            symbol_lookup_failed: Default::default(),
+            symbols_skipped: false,
+            offline: false,
+            max_frames: None,
+            allow_stack_scan: true,
+            walk_cut_short: false,
         }
     }
 }
@@ -62,9 +114,26 @@ pub struct ProcessDump {
     pub dump: Arc<Minidump<'static, Mmap>>,
     pub symbol_paths: Vec<PathBuf>,
     pub symbol_urls: Vec<String>,
+    /// Local symbol files to use for specific modules, by uppercase breakpad debug id
+    pub symbol_overrides: HashMap<String, PathBuf>,
     pub symbol_cache: PathBuf,
     pub clear_cache: bool,
     pub http_timeout_secs: u64,
+    pub symbol_priority: SymbolPriority,
+    pub max_symbol_bytes: Option<u64>,
+    /// How many extra attempts to make at finding each module's symbols
+    pub symbol_retries: u32,
+    /// Run symbol downloads on a multi-threaded runtime with this many workers
+    pub worker_threads: Option<usize>,
+    pub symbolicate: bool,
+    /// Only use local symbols and previously downloaded ones
+    pub offline: bool,
+    /// Cut every stack off after this many frames
+    pub max_frames: Option<usize>,
+    /// If false, stacks end at the first frame that could only be found by scanning
+    pub allow_stack_scan: bool,
+    /// Use (and save) results cached on disk for the same dump and settings
+    pub reuse_result: bool,
 }
 
 pub fn run_processor(
@@ -72,6 +141,9 @@ pub fn run_processor(
     analysis_sender: std::sync::Arc<MinidumpAnalysis>,
     logger: crate::logger::MapLogger,
 ) {
+    // The dump this thread is working on, and its hash once the cache needed it
+    let mut dump_path: Option<PathBuf> = None;
+    let mut dump_hash: Option<u64> = None;
     loop {
         let (lock, condvar) = &*task_receiver;
         let task = {
@@ -86,9 +158,25 @@ pub fn run_processor(
             ProcessorTask::Cancel => {
                 // Do nothing, this is only relevant within the other tasks, now we're just clearing it out
             }
+            ProcessorTask::Quit => return,
             ProcessorTask::ReadDump(path) => {
+                dump_path = Some(path.clone());
+                dump_hash = None;
+                if let Some(problem) = not_a_minidump(&path) {
+                    *analysis_sender.error.lock().unwrap() = Some(problem);
+                    continue;
+                }
                 // Read the dump
-                let dump = Minidump::read_path(path).map(Arc::new);
+                let dump = match decompress_dump(&path) {
+                    Ok(Some(data)) => Minidump::read(data),
+                    Ok(None) => Minidump::read_path(path),
+                    Err(e) => {
+                        *analysis_sender.error.lock().unwrap() =
+                            Some(format!("failed to decompress minidump: {e}"));
+                        continue;
+                    }
+                }
+                .map(Arc::new);
                 *analysis_sender.minidump.lock().unwrap() = Some(dump);
             }
             ProcessorTask::ProcessDump(settings) => {
@@ -96,28 +184,140 @@ pub fn run_processor(
                 *analysis_sender.stats.lock().unwrap() = Default::default();
                 logger.clear();
 
-                // Do the processing
-                let processed = process_minidump(&task_receiver, &analysis_sender, &settings, true);
-                *analysis_sender.processed.lock().unwrap() = processed.map(|p| p.map(Arc::new));
+                // Clearing the symbol cache clears the cached results along with it
+                let cache_path = dump_path
+                    .as_ref()
+                    .filter(|_| settings.reuse_result && !settings.clear_cache)
+                    .and_then(|path| {
+                        if dump_hash.is_none() {
+                            dump_hash = crate::result_cache::hash_dump(path)
+                                .map_err(|e| tracing::warn!("couldn't hash the dump: {e}"))
+                                .ok();
+                        }
+                        Some(crate::result_cache::cache_path(dump_hash?, &settings))
+                    });
+                let cached = cache_path.as_deref().and_then(crate::result_cache::load);
+                let from_cache = cached.is_some();
+
+                // Do the processing. A panic in here would otherwise take down this thread
+                // and leave the UI waiting forever, so report it as a failure instead.
+                let processed = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                    let Some(cached) = cached else {
+                        return process_minidump(
+                            &task_receiver,
+                            &analysis_sender,
+                            &settings,
+                            settings.symbolicate,
+                        );
+                    };
+                    tracing::info!("reusing the result cached for these settings");
+                    let mut processed =
+                        process_minidump(&task_receiver, &analysis_sender, &settings, false);
+                    record_settings(&analysis_sender, &settings, settings.symbolicate);
+                    if let Some(Ok(state)) = &mut processed {
+                        cached.restore(state, &analysis_sender);
+                    }
+                    processed
+                }));
+                let processed = match processed {
+                    Ok(processed) => processed,
+                    Err(panic) => {
+                        let reason = panic
+                            .downcast_ref::<&str>()
+                            .map(|s| s.to_string())
+                            .or_else(|| panic.downcast_ref::<String>().cloned())
+                            .unwrap_or_else(|| "unknown error".to_owned());
+                        tracing::error!("processing crashed: {reason}");
+                        let pending_task = lock.lock().unwrap();
+                        if pending_task.is_none() {
+                            *analysis_sender.error.lock().unwrap() =
+                                Some(format!("internal error: {reason}"));
+                        }
+                        continue;
+                    }
+                };
+
+                // If a new task showed up while we were working, our result is stale and
+                // the controller has already moved on, so don't clobber its state with it.
+                // (Holding the task lock here keeps this atomic with the controller
+                // submitting a new task.)
+                let processed = processed.map(|p| p.map(Arc::new));
+                if let (Some(path), Some(Ok(state))) = (&cache_path, &processed) {
+                    // A run that was cut short by stopping downloads is missing symbols.
+                    // So is one where a server lookup failed: the http supplier reports
+                    // a timeout just like symbols the server doesn't have, so any failed
+                    // lookup might have been a network hiccup we shouldn't keep around.
+                    let stats = analysis_sender.stats.lock().unwrap();
+                    let stopped = stats.stop_fetching.load(atomic::Ordering::Relaxed);
+                    let used_servers = settings.symbolicate
+                        && !settings.offline
+                        && !settings.symbol_urls.is_empty();
+                    let lookup_failed =
+                        used_servers && stats.symbol_lookup_failed.load(atomic::Ordering::Relaxed);
+                    drop(stats);
+                    if !from_cache && !stopped && !lookup_failed {
+                        crate::result_cache::save(path, state, &analysis_sender);
+                    }
+                }
+                let pending_task = lock.lock().unwrap();
+                if pending_task.is_none() {
+                    *analysis_sender.processed.lock().unwrap() = processed;
+                }
             }
         }
     }
 }
 
-fn process_minidump(
+thread_local! {
+    /// Keeps a runtime worker thread's default dispatcher set until it exits
+    static WORKER_DISPATCH: std::cell::RefCell<Option<tracing::dispatcher::DefaultGuard>> =
+        Default::default();
+}
+
+pub fn process_minidump(
     task_receiver: &Arc<(Mutex<Option<ProcessorTask>>, Condvar)>,
     analysis_sender: &Arc<MinidumpAnalysis>,
     settings: &ProcessDump,
     symbolicate: bool,
 ) -> Option<Result<ProcessState, minidump_processor::ProcessError>> {
-    let (symbol_paths, symbol_urls) = if symbolicate {
+    let (mut symbol_paths, symbol_urls) = if symbolicate {
         (settings.symbol_paths.clone(), settings.symbol_urls.clone())
     } else {
         (vec![], vec![])
     };
+    record_settings(analysis_sender, settings, symbolicate);
 
     // Configure the symbolizer and processor
     let symbols_cache = settings.symbol_cache.clone();
+
+    // Symbol archives get extracted to temp dirs, which are deleted once these are
+    // dropped at the end of processing (by then all the symbols we need are loaded).
+    let mut extracted_archives = vec![];
+    for path in &mut symbol_paths {
+        if !is_symbol_archive(path) {
+            continue;
+        }
+        match extract_symbol_archive(path) {
+            Ok(dir) => {
+                *path = dir.path().to_owned();
+                extracted_archives.push(dir);
+            }
+            Err(e) => {
+                tracing::error!("failed to extract symbol archive {}: {e}", path.display());
+            }
+        }
+    }
+
+    // The http supplier always checks the local paths before its cache and the
+    // servers, so local symbols win by default. To let server symbols win instead,
+    // it doesn't get the local paths at all, and they're only searched once the
+    // servers came up empty (see `ServerFirstSupplier`).
+    let server_first =
+        settings.symbol_priority == SymbolPriority::ServerFirst && !symbol_urls.is_empty();
+    // Without any servers, make sure previously downloaded symbols still get used
+    if settings.offline && symbolicate && !settings.clear_cache {
+        symbol_paths.push(symbols_cache.clone());
+    }
     if settings.clear_cache {
         let _ = std::fs::remove_dir_all(&symbols_cache);
     }
@@ -136,27 +336,116 @@ fn process_minidump(
     options.stat_reporter = Some(&stat_reporter);
 
     // Specify a symbol supplier (here we're using the most powerful one, the http supplier)
-    let provider = Symbolizer::new(http_symbol_supplier(
-        symbol_paths,
-        symbol_urls,
-        symbols_cache,
-        symbols_tmp,
-        timeout,
-    ));
-
-    let runtime = tokio::runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()
-        .unwrap();
+    //
+    // The http supplier is backed by a default reqwest client, which follows up to 10
+    // 3xx redirects per request. So a single "index" url that redirects to the actual
+    // symbol store works fine as a symbol server, no special configuration needed.
+    // The supplier always requests `<url>/<debug_file>/<debug_id>/<sym_file>`, so
+    // there's no way to plug in a custom manifest format here.
+    let mut symbol_dirs = symbol_paths.clone();
+    symbol_dirs.push(symbols_cache.clone());
+    let (http_paths, local_fallback) = if server_first {
+        (vec![], Some(SimpleSymbolSupplier::new(symbol_paths)))
+    } else {
+        (symbol_paths, None)
+    };
+    let supplier = ServerFirstSupplier {
+        inner: http_symbol_supplier(
+            http_paths,
+            symbol_urls,
+            symbols_cache.clone(),
+            symbols_tmp,
+            timeout,
+        ),
+        local: local_fallback,
+    };
+    let (fetching, stop_fetching, lookup_failed) = {
+        let stats = analysis_sender.stats.lock().unwrap();
+        (
+            stats.fetching_symbols.clone(),
+            stats.stop_fetching.clone(),
+            stats.symbol_lookup_failed.clone(),
+        )
+    };
+    let supplier = OverrideSupplier {
+        inner: RetryingSupplier {
+            inner: StoppableSupplier {
+                inner: supplier,
+                local: SimpleSymbolSupplier::new(symbol_dirs.clone()),
+                stop: stop_fetching,
+            },
+            retries: settings.symbol_retries,
+            failed: lookup_failed,
+        },
+        overrides: if symbolicate {
+            settings.symbol_overrides.clone()
+        } else {
+            HashMap::new()
+        },
+    };
+    let provider = if let Some(max_bytes) = settings.max_symbol_bytes {
+        Symbolizer::new(TrackingSupplier {
+            inner: SizeLimitedSupplier {
+                inner: supplier,
+                cache: symbols_cache,
+                max_bytes,
+            },
+            fetching,
+        })
+    } else {
+        Symbolizer::new(TrackingSupplier {
+            inner: supplier,
+            fetching,
+        })
+    };
+
+    // Either way, `block_on` polls the processing and status futures below on this
+    // thread, so they can keep borrowing locals. Extra workers only pick up the
+    // tasks the http client spawns for its connections.
+    let runtime = match settings.worker_threads {
+        Some(threads) => {
+            // Log the workers' events to the same document as this thread's
+            let dispatch = tracing::dispatcher::get_default(|dispatch| dispatch.clone());
+            tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(threads)
+                .on_thread_start(move || {
+                    let guard = tracing::dispatcher::set_default(&dispatch);
+                    WORKER_DISPATCH.with(|cell| *cell.borrow_mut() = Some(guard));
+                })
+                .on_thread_stop(|| {
+                    WORKER_DISPATCH.with(|cell| cell.borrow_mut().take());
+                })
+                .enable_all()
+                .build()
+        }
+        None => tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build(),
+    }
+    .unwrap();
 
     let process = || async {
         minidump_processor::process_minidump_with_options(&settings.dump, &provider, options).await
     };
+    // Every stack gets cut down to `max_frames` in the end, so once more frames than
+    // that have been walked for each thread, some stack must be running away (a stack
+    // overflow, or scanning through garbage). There's no way to stop just that one.
+    let walk_ran_away = || {
+        let Some(max_frames) = settings.max_frames else {
+            return false;
+        };
+        let (_done, threads) = stat_reporter.get_thread_count();
+        stat_reporter.get_frame_count() > max_frames.saturating_mul(threads.max(1))
+    };
+    // Resolves to whether the walk ran away, if processing should stop
     let check_status = || async {
         loop {
             if task_receiver.0.lock().unwrap().is_some() {
                 // Cancel processing, controller wants us doing something else
-                return;
+                return false;
+            }
+            if walk_ran_away() {
+                return true;
             }
             // Update stats
             *analysis_sender
@@ -170,12 +459,19 @@ fn process_minidump(
         }
     };
 
-    let state = runtime.block_on(async {
+    let (mut state, ran_away) = runtime.block_on(async {
         tokio::select! {
-            state = process() => Some(state),
-            _ = check_status() => None,
+            state = process() => (Some(state), false),
+            ran_away = check_status() => (None, ran_away),
         }
     });
+    if ran_away {
+        tracing::warn!(
+            "stopped walking, a stack went past {} frames",
+            settings.max_frames.unwrap_or_default()
+        );
+        analysis_sender.stats.lock().unwrap().walk_cut_short = true;
+    }
 
     *analysis_sender
         .stats
@@ -185,5 +481,434 @@ fn process_minidump(
         .lock()
         .unwrap() = provider.pending_stats();
 
+    // ProcessorOptions has no knobs for the stackwalker itself, so stacks that stopped
+    // short of running away still need cutting down to the frame limits.
+    if let Some(Ok(state)) = &mut state {
+        for thread in &mut state.threads {
+            limit_frames(
+                &mut thread.frames,
+                settings.max_frames,
+                settings.allow_stack_scan,
+            );
+        }
+    }
+
+    if let Some(Ok(state)) = &state {
+        *analysis_sender
+            .stats
+            .lock()
+            .unwrap()
+            .symbol_warnings
+            .lock()
+            .unwrap() = check_symbol_architectures(state, &symbol_dirs);
+    }
+
     state
 }
+
+/// How a minidump file is stored
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum DumpCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+/// Works out how a file is compressed from its magic, if it's a minidump at all
+fn dump_compression(path: &Path) -> std::io::Result<Option<DumpCompression>> {
+    use std::io::Read;
+    let mut magic = [0; 4];
+    std::fs::File::open(path)?.read_exact(&mut magic)?;
+    Ok(match magic {
+        [b'M', b'D', b'M', b'P'] => Some(DumpCompression::None),
+        [0x1f, 0x8b, _, _] => Some(DumpCompression::Gzip),
+        [0x28, 0xb5, 0x2f, 0xfd] => Some(DumpCompression::Zstd),
+        _ => None,
+    })
+}
+
+/// Says why the file is obviously not a minidump, without reading all of it.
+/// Files that can't be read at all are left for the real parser to complain about.
+pub fn not_a_minidump(path: &Path) -> Option<String> {
+    let compression = dump_compression(path).ok()?;
+    compression
+        .is_none()
+        .then(|| "Not a minidump (bad magic)".to_owned())
+}
+
+/// Decompresses a gzip or zstd compressed minidump into an anonymous temp file,
+/// so it can be mapped just like an uncompressed one. `None` if it isn't compressed.
+///
+/// (We don't check the decompressed contents are a minidump, parsing it will do that.)
+pub fn decompress_dump(path: &Path) -> std::io::Result<Option<Mmap>> {
+    let file = std::fs::File::open(path)?;
+    let mut decoder: Box<dyn std::io::Read> = match dump_compression(path)? {
+        Some(DumpCompression::Gzip) => Box::new(flate2::read::GzDecoder::new(file)),
+        Some(DumpCompression::Zstd) => Box::new(
+            ruzstd::StreamingDecoder::new(file)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?,
+        ),
+        Some(DumpCompression::None) | None => return Ok(None),
+    };
+    let mut decompressed = tempfile::tempfile()?;
+    std::io::copy(&mut decoder, &mut decompressed)?;
+    // The file is gone as soon as the mapping is, even on Windows
+    let data = unsafe { Mmap::map(&decompressed)? };
+    Ok(Some(data))
+}
+
+/// Lets the UI know how the processing it's watching was set up
+fn record_settings(analysis_sender: &MinidumpAnalysis, settings: &ProcessDump, symbolicate: bool) {
+    let mut stats = analysis_sender.stats.lock().unwrap();
+    stats.symbols_skipped = !symbolicate;
+    stats.offline = settings.offline;
+    stats.max_frames = settings.max_frames;
+    stats.allow_stack_scan = settings.allow_stack_scan;
+}
+
+/// Whether the stackwalker only found this frame by scanning the stack for
+/// something that looks like a return address (and so may well be bogus).
+pub fn is_scanned(frame: &StackFrame) -> bool {
+    matches!(frame.trust, FrameTrust::Scan | FrameTrust::CfiScan)
+}
+
+/// Truncates a stack according to the limits in [`ProcessDump`].
+pub fn limit_frames(
+    frames: &mut Vec<StackFrame>,
+    max_frames: Option<usize>,
+    allow_stack_scan: bool,
+) {
+    if !allow_stack_scan {
+        if let Some(first_scanned) = frames.iter().position(is_scanned) {
+            frames.truncate(first_scanned);
+        }
+    }
+    if let Some(max_frames) = max_frames {
+        frames.truncate(max_frames);
+    }
+}
+
+/// Whether a symbol path is a zip of symbols (as CI systems tend to produce)
+/// rather than a directory.
+fn is_symbol_archive(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+fn extract_symbol_archive(path: &Path) -> Result<tempfile::TempDir, zip::result::ZipError> {
+    let dir = tempfile::tempdir()?;
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    archive.extract(dir.path())?;
+    Ok(dir)
+}
+
+/// A symbol supplier that refuses symbol files bigger than `max_bytes`.
+///
+/// The http supplier doesn't let us abort a download partway through, so an
+/// oversized file still gets downloaded, but we then delete it from the cache
+/// and report the module as having no symbols instead of parsing it.
+struct SizeLimitedSupplier<T> {
+    inner: T,
+    cache: PathBuf,
+    max_bytes: u64,
+}
+
+#[async_trait]
+impl<T: SymbolSupplier + Send + Sync> SymbolSupplier for SizeLimitedSupplier<T> {
+    async fn locate_symbols(
+        &self,
+        module: &(dyn Module + Sync),
+    ) -> Result<LocateSymbolsResult, SymbolError> {
+        if let Ok(path) = self.inner.locate_file(module, FileKind::BreakpadSym).await {
+            let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            if size > self.max_bytes {
+                tracing::warn!(
+                    "symbols for {} are {} bytes, more than the limit of {} bytes, ignoring them",
+                    module.code_file(),
+                    size,
+                    self.max_bytes,
+                );
+                // Only clean up our own downloads, never the user's local symbols!
+                if path.starts_with(&self.cache) {
+                    let _ = std::fs::remove_file(&path);
+                }
+                return Err(SymbolError::NotFound);
+            }
+        }
+        self.inner.locate_symbols(module).await
+    }
+
+    async fn locate_file(
+        &self,
+        module: &(dyn Module + Sync),
+        file_kind: FileKind,
+    ) -> Result<PathBuf, FileError> {
+        self.inner.locate_file(module, file_kind).await
+    }
+}
+
+/// A symbol supplier that falls back on `local` when `inner` (the http supplier,
+/// without any local paths) can't find the symbols on the servers. Without `local`,
+/// it's just `inner`, which checks its local paths first.
+struct ServerFirstSupplier<T> {
+    inner: T,
+    local: Option<SimpleSymbolSupplier>,
+}
+
+#[async_trait]
+impl<T: SymbolSupplier + Send + Sync> SymbolSupplier for ServerFirstSupplier<T> {
+    async fn locate_symbols(
+        &self,
+        module: &(dyn Module + Sync),
+    ) -> Result<LocateSymbolsResult, SymbolError> {
+        let result = self.inner.locate_symbols(module).await;
+        match (&self.local, &result) {
+            (Some(local), Err(SymbolError::NotFound)) => local.locate_symbols(module).await,
+            _ => result,
+        }
+    }
+
+    async fn locate_file(
+        &self,
+        module: &(dyn Module + Sync),
+        file_kind: FileKind,
+    ) -> Result<PathBuf, FileError> {
+        let result = self.inner.locate_file(module, file_kind).await;
+        match (&self.local, &result) {
+            (Some(local), Err(FileError::NotFound)) => local.locate_file(module, file_kind).await,
+            _ => result,
+        }
+    }
+}
+
+/// A symbol supplier that switches over to only looking at local symbols once `stop`
+/// is set. Downloads that are already in flight still get to finish.
+struct StoppableSupplier<T> {
+    inner: T,
+    local: SimpleSymbolSupplier,
+    stop: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl<T: SymbolSupplier + Send + Sync> SymbolSupplier for StoppableSupplier<T> {
+    async fn locate_symbols(
+        &self,
+        module: &(dyn Module + Sync),
+    ) -> Result<LocateSymbolsResult, SymbolError> {
+        if self.stop.load(atomic::Ordering::Relaxed) {
+            self.local.locate_symbols(module).await
+        } else {
+            self.inner.locate_symbols(module).await
+        }
+    }
+
+    async fn locate_file(
+        &self,
+        module: &(dyn Module + Sync),
+        file_kind: FileKind,
+    ) -> Result<PathBuf, FileError> {
+        if self.stop.load(atomic::Ordering::Relaxed) {
+            self.local.locate_file(module, file_kind).await
+        } else {
+            self.inner.locate_file(module, file_kind).await
+        }
+    }
+}
+
+/// A symbol supplier that tries again (with exponential backoff) when a lookup fails.
+///
+/// The http supplier reports a failed download the same way as a server that just
+/// doesn't have the symbols, so modules without any symbols get retried too.
+/// That's why this is off by default.
+struct RetryingSupplier<T> {
+    inner: T,
+    retries: u32,
+    /// Set once a lookup has failed for good
+    failed: Arc<AtomicBool>,
+}
+
+impl<T> RetryingSupplier<T> {
+    const FIRST_BACKOFF: std::time::Duration = std::time::Duration::from_millis(500);
+}
+
+#[async_trait]
+impl<T: SymbolSupplier + Send + Sync> SymbolSupplier for RetryingSupplier<T> {
+    async fn locate_symbols(
+        &self,
+        module: &(dyn Module + Sync),
+    ) -> Result<LocateSymbolsResult, SymbolError> {
+        let mut backoff = Self::FIRST_BACKOFF;
+        let mut attempt = 0;
+        loop {
+            let result = self.inner.locate_symbols(module).await;
+            match result {
+                // Retrying can't conjure up a missing debug id
+                Err(SymbolError::MissingDebugFileOrId) => return result,
+                Err(e) if attempt < self.retries => {
+                    attempt += 1;
+                    tracing::warn!(
+                        "couldn't get symbols for {} ({e}), retrying in {}ms (attempt {attempt}/{})",
+                        module.code_file(),
+                        backoff.as_millis(),
+                        self.retries,
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(_) => {
+                    self.failed.store(true, atomic::Ordering::Relaxed);
+                    return result;
+                }
+                Ok(_) => return result,
+            }
+        }
+    }
+
+    async fn locate_file(
+        &self,
+        module: &(dyn Module + Sync),
+        file_kind: FileKind,
+    ) -> Result<PathBuf, FileError> {
+        self.inner.locate_file(module, file_kind).await
+    }
+}
+
+/// A symbol supplier that uses the user's hand-picked `.sym` file for a module
+/// instead of looking it up anywhere, for when automatic lookup can't find it.
+struct OverrideSupplier<T> {
+    inner: T,
+    overrides: HashMap<String, PathBuf>,
+}
+
+impl<T> OverrideSupplier<T> {
+    fn override_for(&self, module: &(dyn Module + Sync)) -> Option<&PathBuf> {
+        let debug_id = module.debug_identifier()?.breakpad().to_string();
+        self.overrides.get(&debug_id.to_uppercase())
+    }
+}
+
+#[async_trait]
+impl<T: SymbolSupplier + Send + Sync> SymbolSupplier for OverrideSupplier<T> {
+    async fn locate_symbols(
+        &self,
+        module: &(dyn Module + Sync),
+    ) -> Result<LocateSymbolsResult, SymbolError> {
+        if let Some(path) = self.override_for(module) {
+            match SymbolFile::from_file(path) {
+                Ok(symbols) => {
+                    return Ok(LocateSymbolsResult {
+                        symbols,
+                        extra_debug_info: None,
+                    })
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "failed to load symbol override {} for {}: {e}, looking it up as usual",
+                        path.display(),
+                        module.code_file(),
+                    );
+                }
+            }
+        }
+        self.inner.locate_symbols(module).await
+    }
+
+    async fn locate_file(
+        &self,
+        module: &(dyn Module + Sync),
+        file_kind: FileKind,
+    ) -> Result<PathBuf, FileError> {
+        if let Some(path) = self.override_for(module) {
+            if matches!(file_kind, FileKind::BreakpadSym) && path.exists() {
+                return Ok(path.clone());
+            }
+        }
+        self.inner.locate_file(module, file_kind).await
+    }
+}
+
+/// A symbol supplier that keeps track of which modules it's currently looking up,
+/// since `PendingSymbolStats` only has counts.
+struct TrackingSupplier<T> {
+    inner: T,
+    fetching: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl<T: SymbolSupplier + Send + Sync> SymbolSupplier for TrackingSupplier<T> {
+    async fn locate_symbols(
+        &self,
+        module: &(dyn Module + Sync),
+    ) -> Result<LocateSymbolsResult, SymbolError> {
+        let name = basename(&module.code_file()).to_owned();
+        self.fetching.lock().unwrap().push(name.clone());
+        let result = self.inner.locate_symbols(module).await;
+        let mut fetching = self.fetching.lock().unwrap();
+        if let Some(idx) = fetching.iter().position(|fetched| *fetched == name) {
+            fetching.remove(idx);
+        }
+        result
+    }
+
+    async fn locate_file(
+        &self,
+        module: &(dyn Module + Sync),
+        file_kind: FileKind,
+    ) -> Result<PathBuf, FileError> {
+        self.inner.locate_file(module, file_kind).await
+    }
+}
+
+/// The symbolizer doesn't care if a symbol file was built for the wrong architecture,
+/// it will just produce garbage. So find the symbol files we (probably) used and check
+/// the architecture listed in their `MODULE` line against the dump's.
+fn check_symbol_architectures(state: &ProcessState, symbol_dirs: &[PathBuf]) -> Vec<String> {
+    let expected = match state.system_info.cpu {
+        Cpu::X86 => "x86",
+        Cpu::X86_64 => "x86_64",
+        Cpu::Ppc => "ppc",
+        Cpu::Ppc64 => "ppc64",
+        Cpu::Sparc => "sparc",
+        Cpu::Arm => "arm",
+        Cpu::Arm64 => "arm64",
+        Cpu::Mips => "mips",
+        Cpu::Mips64 => "mips64",
+        _ => return vec![],
+    };
+
+    let mut warnings = vec![];
+    for module in state.modules.iter() {
+        let Some(lookup) = breakpad_sym_lookup(module) else {
+            continue;
+        };
+        let arch = symbol_dirs
+            .iter()
+            .find_map(|dir| symbol_file_arch(&dir.join(&lookup.cache_rel)));
+        if let Some(arch) = arch {
+            let matches = arch == expected || (expected == "arm64" && arch == "arm64e");
+            if !matches {
+                warnings.push(format!(
+                    "{}: symbols are for {arch}, but the minidump is {expected}",
+                    lookup.debug_file
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+/// Reads the architecture out of a symbol file's `MODULE <os> <arch> <id> <name>` line
+fn symbol_file_arch(path: &Path) -> Option<String> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut first_line = String::new();
+    std::io::BufReader::new(file)
+        .read_line(&mut first_line)
+        .ok()?;
+    let mut parts = first_line.split_whitespace();
+    if parts.next()? != "MODULE" {
+        return None;
+    }
+    parts.nth(1).map(|arch| arch.to_owned())
+}
@@ -0,0 +1,282 @@
+//! Processed results saved next to the symbol cache, so reopening a dump with the
+//! same settings doesn't have to fetch symbols and walk every stack all over again.
+//!
+//! `ProcessState` can be written out (as the same JSON `minidump-stackwalk --json`
+//! produces) but not read back in. So a cached result gets rebuilt by doing a quick
+//! walk without symbols, which fills in everything that comes straight from the dump,
+//! and then swapping in the stacks and symbol stats from the JSON. The few bits of
+//! the frames the JSON leaves out get saved next to it. Only the first frame of each
+//! stack has its registers, the others just have their addresses.
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use breakpad_symbols::SymbolStats;
+use minidump::{MinidumpContext, MinidumpContextValidity, MinidumpModuleList, Module};
+use minidump_processor::ProcessState;
+use minidump_unwind::{FrameTrust, PendingSymbolStats, StackFrame};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::processor::{MinidumpAnalysis, ProcessDump};
+
+#[derive(Serialize, Deserialize)]
+pub struct CachedResult {
+    symbol_warnings: Vec<String>,
+    symbols_processed: u64,
+    symbols_requested: u64,
+    /// What `ProcessState::print_json` wrote
+    state: Value,
+    /// What the JSON doesn't have of each thread's frames
+    frames: Vec<Vec<CachedFrame>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedFrame {
+    resume_address: u64,
+    source_line_base: Option<u64>,
+}
+
+/// Hashes the whole dump file, so edits to it (or a different dump at the same
+/// path) never pick up a stale result.
+pub fn hash_dump(path: &Path) -> std::io::Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buf = vec![0; 1024 * 1024];
+    loop {
+        let len = file.read(&mut buf)?;
+        if len == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buf[..len]);
+    }
+}
+
+/// Where the result of processing the dump with `settings` gets cached
+pub fn cache_path(dump_hash: u64, settings: &ProcessDump) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    // Newer versions may well process things differently
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    dump_hash.hash(&mut hasher);
+    settings.symbol_paths.hash(&mut hasher);
+    settings.symbol_urls.hash(&mut hasher);
+    let mut overrides = settings.symbol_overrides.iter().collect::<Vec<_>>();
+    overrides.sort();
+    overrides.hash(&mut hasher);
+    // Rebuilding local symbols has to miss the cache, so the files count too
+    for path in settings
+        .symbol_paths
+        .iter()
+        .chain(settings.symbol_overrides.values())
+    {
+        hash_symbol_files(path, &mut hasher);
+    }
+    settings.symbol_priority.hash(&mut hasher);
+    settings.max_symbol_bytes.hash(&mut hasher);
+    settings.symbolicate.hash(&mut hasher);
+    settings.offline.hash(&mut hasher);
+    settings.max_frames.hash(&mut hasher);
+    settings.allow_stack_scan.hash(&mut hasher);
+    settings
+        .symbol_cache
+        .join("results")
+        .join(format!("{:016x}.json", hasher.finish()))
+}
+
+/// Hashes the size and modification time of every file at or under `path`
+fn hash_symbol_files(path: &Path, hasher: &mut DefaultHasher) {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return;
+    };
+    if !metadata.is_dir() {
+        path.hash(hasher);
+        metadata.len().hash(hasher);
+        metadata.modified().ok().hash(hasher);
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    let mut entries = entries
+        .filter_map(|entry| entry.ok())
+        // Don't follow links to directories, they could loop forever
+        .filter(|entry| entry.file_type().map_or(false, |kind| !kind.is_symlink()))
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    entries.sort();
+    for entry in entries {
+        hash_symbol_files(&entry, hasher);
+    }
+}
+
+pub fn load(path: &Path) -> Option<CachedResult> {
+    // Not existing is the usual case, so only complain about bad contents
+    let bytes = std::fs::read(path).ok()?;
+    serde_json::from_slice(&bytes)
+        .map_err(|e| tracing::warn!("ignoring unreadable cached result {}: {e}", path.display()))
+        .ok()
+}
+
+/// Writes out `state`, along with the symbol stats the UI shows next to it
+pub fn save(path: &Path, state: &ProcessState, analysis: &MinidumpAnalysis) {
+    let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+        let mut json = Vec::new();
+        state.print_json(&mut json, false)?;
+        let stats = analysis.stats.lock().unwrap();
+        let pending = stats.pending_symbols.lock().unwrap().clone();
+        let cached = CachedResult {
+            symbol_warnings: stats.symbol_warnings.lock().unwrap().clone(),
+            symbols_processed: pending.symbols_processed,
+            symbols_requested: pending.symbols_requested,
+            state: serde_json::from_slice(&json)?,
+            frames: state
+                .threads
+                .iter()
+                .map(|thread| {
+                    thread
+                        .frames
+                        .iter()
+                        .map(|frame| CachedFrame {
+                            resume_address: frame.resume_address,
+                            source_line_base: frame.source_line_base,
+                        })
+                        .collect()
+                })
+                .collect(),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_vec(&cached)?)?;
+        Ok(())
+    })();
+    if let Err(e) = result {
+        tracing::error!("failed to cache result in {}: {e}", path.display());
+    }
+}
+
+impl CachedResult {
+    /// Puts the cached stacks and symbol stats into `state`, which should come from
+    /// walking the same dump without symbols.
+    pub fn restore(self, state: &mut ProcessState, analysis: &MinidumpAnalysis) {
+        let threads = self.state["threads"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let modules = &state.modules;
+        for (thread_idx, (thread, cached)) in state.threads.iter_mut().zip(&threads).enumerate() {
+            // The thread's own registers, as the walk started with them
+            let Some(context) = thread.frames.first().map(|frame| frame.context.clone()) else {
+                continue;
+            };
+            let cached_frames = cached["frames"].as_array().cloned().unwrap_or_default();
+            let extras = self.frames.get(thread_idx);
+            thread.frames = cached_frames
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, cached)| {
+                    let mut context = context.clone();
+                    if idx > 0 {
+                        context.valid = MinidumpContextValidity::Some(Default::default());
+                    }
+                    let extra = extras.and_then(|frames| frames.get(idx));
+                    restore_frame(modules, context, cached, extra)
+                })
+                .collect();
+        }
+
+        let cached_modules = self.state["modules"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        state.symbol_stats.clear();
+        for cached in &cached_modules {
+            let Some(base) = hex(&cached["base_addr"]) else {
+                continue;
+            };
+            let Some(module) = state.modules.iter().find(|m| m.base_address() == base) else {
+                continue;
+            };
+            let mut stats = SymbolStats::default();
+            stats.symbol_url = cached["symbol_url"].as_str().map(str::to_owned);
+            stats.loaded_symbols = cached["loaded_symbols"].as_bool().unwrap_or(false);
+            stats.corrupt_symbols = cached["corrupt_symbols"].as_bool().unwrap_or(false);
+            state.symbol_stats.insert(module.name.clone(), stats);
+        }
+
+        let stats = analysis.stats.lock().unwrap();
+        *stats.symbol_warnings.lock().unwrap() = self.symbol_warnings;
+        *stats.pending_symbols.lock().unwrap() = PendingSymbolStats {
+            symbols_processed: self.symbols_processed,
+            symbols_requested: self.symbols_requested,
+        };
+    }
+}
+
+fn restore_frame(
+    modules: &MinidumpModuleList,
+    context: MinidumpContext,
+    cached: &Value,
+    extra: Option<&CachedFrame>,
+) -> Option<StackFrame> {
+    let instruction = hex(&cached["offset"])?;
+    let trust = match cached["trust"].as_str()? {
+        "context" => FrameTrust::Context,
+        "prewalked" => FrameTrust::PreWalked,
+        "cfi" => FrameTrust::CallFrameInfo,
+        "frame_pointer" => FrameTrust::FramePointer,
+        "cfi_scan" => FrameTrust::CfiScan,
+        "scan" => FrameTrust::Scan,
+        "inline" => FrameTrust::Inline,
+        _ => FrameTrust::None,
+    };
+    let mut frame = StackFrame::from_context(context, trust);
+    frame.instruction = instruction;
+    frame.resume_address = extra.map_or(instruction, |extra| extra.resume_address);
+    frame.source_line_base = extra.and_then(|extra| extra.source_line_base);
+    frame.module = modules.module_at_address(instruction).cloned();
+    frame.unloaded_modules = cached["unloaded_modules"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|unloaded| {
+            let name = unloaded["module"].as_str()?.to_owned();
+            let offsets = unloaded["offsets"]
+                .as_array()?
+                .iter()
+                .filter_map(hex)
+                .collect();
+            Some((name, offsets))
+        })
+        .collect();
+    frame.function_name = cached["function"].as_str().map(str::to_owned);
+    frame.function_base =
+        hex(&cached["function_offset"]).and_then(|offset| instruction.checked_sub(offset));
+    frame.source_file_name = cached["file"].as_str().map(str::to_owned);
+    frame.source_line = cached["line"].as_u64().map(|line| line as u32);
+    #[cfg(feature = "inline")]
+    {
+        frame.inlines = cached["inlines"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|inline| {
+                Some(minidump_unwind::InlineFrame {
+                    function_name: inline["function"].as_str()?.to_owned(),
+                    source_file_name: inline["file"].as_str().map(str::to_owned),
+                    source_line: inline["line"].as_u64().map(|line| line as u32),
+                })
+            })
+            .collect();
+    }
+    Some(frame)
+}
+
+/// Reads one of the `"0x..."` strings the JSON uses for addresses
+fn hex(value: &Value) -> Option<u64> {
+    u64::from_str_radix(value.as_str()?.trim_start_matches("0x"), 16).ok()
+}
@@ -0,0 +1,88 @@
+//! The parts of `Settings` that are remembered across runs.
+
+use crate::{Settings, Theme};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Everything is optional so that older (or hand-edited) files still load,
+/// with anything missing falling back to the usual defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedSettings {
+    pub symbol_urls: Option<Vec<(String, bool)>>,
+    pub symbol_paths: Option<Vec<(String, bool)>>,
+    pub symbol_cache: Option<(String, bool)>,
+    pub symbol_overrides: Option<Vec<(String, String)>>,
+    pub http_timeout_secs: Option<String>,
+    pub raw_dump_brief: Option<bool>,
+    pub sensitive_env_patterns: Option<String>,
+    pub theme: Option<Theme>,
+    pub ui_scale: Option<f32>,
+    pub demangle_names: Option<bool>,
+    pub source_root: Option<String>,
+    pub editor_url: Option<String>,
+    /// Most recent first
+    pub recent_paths: Vec<PathBuf>,
+}
+
+fn settings_path() -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("minidump-debugger")
+            .join("settings.json"),
+    )
+}
+
+impl SavedSettings {
+    pub fn load() -> Self {
+        let Some(path) = settings_path() else {
+            return Self::default();
+        };
+        // Not existing yet is the normal first-run case, so only complain about bad contents
+        let Ok(bytes) = std::fs::read(&path) else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            tracing::warn!("ignoring unreadable settings {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        let Some(path) = settings_path() else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| {
+                let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::from)?;
+                std::fs::write(&path, json)
+            });
+        if let Err(e) = result {
+            tracing::error!("failed to save settings to {}: {e}", path.display());
+        }
+    }
+
+    /// Picks up the current settings, except for any that were given on the
+    /// command line: those only apply to this session, so the saved ones are kept.
+    pub fn update_from(&mut self, settings: &Settings) {
+        if !settings.symbol_urls_from_cli {
+            self.symbol_urls = Some(settings.symbol_urls.clone());
+        }
+        if !settings.symbol_paths_from_cli {
+            self.symbol_paths = Some(settings.symbol_paths.clone());
+        }
+        self.symbol_cache = Some(settings.symbol_cache.clone());
+        self.symbol_overrides = Some(settings.symbol_overrides.clone());
+        self.http_timeout_secs = Some(settings.http_timeout_secs.clone());
+        self.raw_dump_brief = Some(settings.raw_dump_brief);
+        self.sensitive_env_patterns = Some(settings.sensitive_env_patterns.clone());
+        self.theme = Some(settings.theme);
+        self.ui_scale = Some(settings.ui_scale);
+        self.demangle_names = Some(settings.demangle_names);
+        self.source_root = Some(settings.source_root.clone());
+        self.editor_url = Some(settings.editor_url.clone());
+        self.recent_paths = settings.recent_paths.clone();
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{diff_indices, frame_signature, MyApp};
+use eframe::egui;
+use egui::{Color32, ComboBox, RichText, Ui};
+use egui_extras::{Size, TableBuilder};
+use minidump_processor::ProcessState;
+use minidump_unwind::CallStack;
+
+impl MyApp {
+    pub fn ui_compare(&mut self, ui: &mut Ui, _ctx: &egui::Context) {
+        let Some(Ok(state)) = &self.doc.processed else {
+            ui.label("This minidump hasn't been processed yet");
+            return;
+        };
+        let candidates = self
+            .other_docs
+            .iter()
+            .filter(|doc| matches!(doc.processed, Some(Ok(_))))
+            .collect::<Vec<_>>();
+        let Some(default_doc) = candidates.first() else {
+            ui.label("Open and process another minidump to compare this one with");
+            return;
+        };
+        // The document we were comparing against may have been closed since
+        let other_doc = candidates
+            .iter()
+            .find(|doc| Some(doc.id) == self.compare_with)
+            .unwrap_or(default_doc);
+        let Some(Ok(other_state)) = &other_doc.processed else {
+            unreachable!("only processed documents are candidates");
+        };
+
+        let mut compare_with = other_doc.id;
+        ui.horizontal(|ui| {
+            ui.label("compare with");
+            ComboBox::from_id_source("compare with")
+                .selected_text(other_doc.name())
+                .show_ui(ui, |ui| {
+                    for doc in &candidates {
+                        ui.selectable_value(&mut compare_with, doc.id, doc.name());
+                    }
+                });
+        });
+        ui.separator();
+
+        let (Some(stack), Some(other_stack)) = (crashing_stack(state), crashing_stack(other_state))
+        else {
+            ui.label("Both minidumps need a crashing thread to compare");
+            return;
+        };
+        let signatures = stack_signatures(stack);
+        let other_signatures = stack_signatures(other_stack);
+        let rows = diff_indices(&signatures, &other_signatures);
+        let matching = rows
+            .iter()
+            .filter(|(lhs, rhs)| lhs.is_some() && rhs.is_some())
+            .count();
+        let crash_reason = |state: &ProcessState| {
+            state
+                .exception_info
+                .as_ref()
+                .map_or_else(|| "<no crash>".to_owned(), |info| info.reason.to_string())
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{}: {}", self.doc.name(), crash_reason(state)));
+            ui.separator();
+            ui.label(format!(
+                "{}: {}",
+                other_doc.name(),
+                crash_reason(other_state)
+            ));
+        });
+        if matching == rows.len() {
+            ui.label("The crashing backtraces are identical");
+        } else {
+            ui.label(format!(
+                "{matching} of {} frames line up",
+                signatures.len().max(other_signatures.len())
+            ));
+        }
+        ui.add_space(10.0);
+
+        let removed = Color32::from_rgb(200, 60, 60);
+        let added = Color32::from_rgb(60, 160, 60);
+        TableBuilder::new(ui)
+            .striped(true)
+            .cell_layout(egui::Layout::left_to_right().with_cross_align(egui::Align::Center))
+            .column(Size::initial(40.0).at_least(30.0))
+            .column(Size::relative(0.45).at_least(60.0))
+            .column(Size::initial(40.0).at_least(30.0))
+            .column(Size::remainder().at_least(60.0))
+            .resizable(true)
+            .clip(true)
+            .header(20.0, |mut header| {
+                header.col(|ui| {
+                    ui.heading("#");
+                });
+                header.col(|ui| {
+                    ui.heading(self.doc.name());
+                });
+                header.col(|ui| {
+                    ui.heading("#");
+                });
+                header.col(|ui| {
+                    ui.heading(other_doc.name());
+                });
+            })
+            .body(|body| {
+                body.rows(18.0, rows.len(), |row_idx, mut row| {
+                    let (lhs, rhs) = rows[row_idx];
+                    let same = lhs.is_some() && rhs.is_some();
+                    for (idx, signatures, color) in
+                        [(lhs, &signatures, removed), (rhs, &other_signatures, added)]
+                    {
+                        row.col(|ui| {
+                            if let Some(idx) = idx {
+                                ui.label(idx.to_string());
+                            }
+                        });
+                        row.col(|ui| {
+                            if let Some(idx) = idx {
+                                let text = RichText::new(&signatures[idx]).monospace();
+                                ui.label(if same { text } else { text.color(color) });
+                            }
+                        });
+                    }
+                });
+            });
+
+        self.compare_with = Some(compare_with);
+    }
+}
+
+fn crashing_stack(state: &ProcessState) -> Option<&CallStack> {
+    state.threads.get(state.requesting_thread?)
+}
+
+fn stack_signatures(stack: &CallStack) -> Vec<String> {
+    stack
+        .frames
+        .iter()
+        .map(|frame| {
+            let mut signature = String::new();
+            let _ = frame_signature(&mut signature, frame);
+            signature
+        })
+        .collect()
+}
@@ -0,0 +1,175 @@
+//! Disassembly of the code around a frame's instruction, for frames that
+//! symbols can't tell us much about.
+
+use crate::MyApp;
+use eframe::egui;
+use egui::{Color32, RichText, ScrollArea, Ui};
+use iced_x86::Formatter;
+use minidump::{system_info::Cpu, MinidumpMemoryList};
+use minidump_processor::ProcessState;
+use minidump_unwind::FrameTrust;
+use yaxpeax_arch::{Decoder, U8Reader};
+
+/// How many bytes before and after the instruction to disassemble
+const DISASM_WINDOW: u64 = 64;
+
+struct DisasmLine {
+    addr: u64,
+    bytes: Vec<u8>,
+    text: String,
+}
+
+impl MyApp {
+    pub fn ui_disasm(&self, ui: &mut Ui, state: &ProcessState) {
+        let ui_state = &self.doc.processed_ui_state;
+        let Some(frame) = state
+            .threads
+            .get(ui_state.cur_thread)
+            .and_then(|thread| thread.frames.get(ui_state.cur_frame))
+        else {
+            ui.label("Select a frame to see its disassembly");
+            return;
+        };
+        let Some(Ok(dump)) = &self.doc.minidump else {
+            return;
+        };
+        let addr = frame.instruction;
+        // A caller's `instruction` points somewhere inside its call (it's the return
+        // address minus a bit), so decode from the return address, which really is
+        // an instruction boundary, and mark the instruction containing `addr`.
+        let boundary = if frame.trust == FrameTrust::Context {
+            addr
+        } else {
+            frame.resume_address
+        };
+        let memory = dump.get_stream::<MinidumpMemoryList>().ok();
+        let Some(region) = memory
+            .as_ref()
+            .and_then(|memory| memory.memory_at_address(boundary))
+        else {
+            ui.label(format!(
+                "The minidump doesn't contain the memory at {}",
+                self.format_addr(addr)
+            ));
+            return;
+        };
+        let Some(lines) = disassemble(
+            state.system_info.cpu,
+            region.base_address,
+            region.bytes,
+            boundary,
+        ) else {
+            ui.label(format!(
+                "Disassembly isn't supported for {}",
+                state.system_info.cpu
+            ));
+            return;
+        };
+
+        let highlight = Color32::from_rgb(220, 140, 0);
+        // Only scroll to the instruction when a new one gets selected, so the user
+        // can still scroll around
+        let scrolled_id = ui.id().with("disasm scrolled to");
+        let needs_scroll = ui.ctx().data().get_temp::<u64>(scrolled_id) != Some(addr);
+        ui.ctx().data().insert_temp(scrolled_id, addr);
+        ScrollArea::both().auto_shrink([false; 2]).show(ui, |ui| {
+            for line in lines {
+                let bytes = line
+                    .bytes
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let is_cur = (line.addr..line.addr + line.bytes.len() as u64).contains(&addr);
+                let marker = if is_cur { "▶" } else { " " };
+                let text = RichText::new(format!(
+                    "{marker} {}  {bytes:<30} {}",
+                    self.format_addr(line.addr),
+                    line.text
+                ))
+                .monospace();
+                let label = ui.label(if is_cur { text.color(highlight) } else { text });
+                if is_cur && needs_scroll {
+                    label.scroll_to_me(Some(egui::Align::Center));
+                }
+            }
+        });
+    }
+}
+
+/// Disassembles the code around `addr` (which must be in `bytes`), or None if
+/// we don't have a disassembler for this cpu.
+fn disassemble(cpu: Cpu, base_address: u64, bytes: &[u8], addr: u64) -> Option<Vec<DisasmLine>> {
+    let offset = (addr - base_address) as usize;
+    let end = (offset + DISASM_WINDOW as usize).min(bytes.len());
+    match cpu {
+        Cpu::X86 | Cpu::X86_64 => {
+            let bitness = if cpu == Cpu::X86 { 32 } else { 64 };
+            // x86 instructions have variable lengths, so we can't just decode backwards.
+            // Instead find the earliest start that decodes to an instruction boundary
+            // right at `addr`, and go from there.
+            let earliest = offset.saturating_sub(DISASM_WINDOW as usize);
+            let lines = (earliest..=offset)
+                .map(|start| disassemble_x86(bitness, base_address, bytes, start, end))
+                .find(|lines| lines.iter().any(|line| line.addr == addr))?;
+            Some(lines)
+        }
+        Cpu::Arm64 => {
+            // Every instruction is 4 aligned bytes, which makes things easy
+            let start = offset.saturating_sub(DISASM_WINDOW as usize) & !3;
+            let decoder = yaxpeax_arm::armv8::a64::InstDecoder::default();
+            let lines = bytes[start..end]
+                .chunks_exact(4)
+                .enumerate()
+                .map(|(idx, inst_bytes)| {
+                    let mut reader = U8Reader::new(inst_bytes);
+                    let text = match decoder.decode(&mut reader) {
+                        Ok(inst) => inst.to_string(),
+                        Err(e) => format!("(bad: {e})"),
+                    };
+                    DisasmLine {
+                        addr: base_address + (start + idx * 4) as u64,
+                        bytes: inst_bytes.to_vec(),
+                        text,
+                    }
+                })
+                .collect();
+            Some(lines)
+        }
+        _ => None,
+    }
+}
+
+fn disassemble_x86(
+    bitness: u32,
+    base_address: u64,
+    bytes: &[u8],
+    start: usize,
+    end: usize,
+) -> Vec<DisasmLine> {
+    let mut decoder = iced_x86::Decoder::with_ip(
+        bitness,
+        &bytes[start..end],
+        base_address + start as u64,
+        iced_x86::DecoderOptions::NONE,
+    );
+    let mut formatter = iced_x86::IntelFormatter::new();
+    let mut lines = vec![];
+    let mut inst = iced_x86::Instruction::default();
+    while decoder.can_decode() {
+        decoder.decode_out(&mut inst);
+        let inst_start = (inst.ip() - base_address) as usize;
+        let mut text = String::new();
+        if inst.is_invalid() {
+            text.push_str("(bad)");
+        } else {
+            formatter.format(&inst, &mut text);
+        }
+        lines.push(DisasmLine {
+            addr: inst.ip(),
+            bytes: bytes[inst_start..inst_start + inst.len()].to_vec(),
+            text,
+        });
+    }
+    lines
+}
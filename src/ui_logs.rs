@@ -1,16 +1,56 @@
-use crate::MyApp;
+use crate::logger::{search_matches, MapLogger};
+use crate::{MyApp, Tab};
 use eframe::egui;
-use egui::{ComboBox, TextStyle, Ui};
+use egui::text::{CCursor, LayoutJob, TextFormat};
+use egui::{Color32, ComboBox, RichText, Sense, Shape, TextStyle, Ui};
+use std::collections::HashMap;
+use tracing::Level;
 
 pub struct LogUiState {
     pub cur_thread: Option<usize>,
     pub cur_frame: Option<usize>,
+    pub diff_next_frame: bool,
+    pub thread_id_query: String,
+    pub show_all_fields: bool,
+    pub search: LogSearch,
+    /// The most verbose level of message to show
+    pub level_threshold: Level,
+    pub show_timestamps: bool,
+    /// Show the logs line by line, colored by level, instead of as plain text
+    pub rich: bool,
+    /// How far down the logs of each (thread, frame) were scrolled, so that their
+    /// place isn't lost while they grow or when switching back and forth
+    pub scroll_offsets: HashMap<(Option<usize>, Option<usize>), f32>,
+    /// Which (thread, frame) the logs were last shown for, so a saved scroll offset
+    /// only gets applied when switching to them, and doesn't fight the user's scrolling
+    pub shown_logs: Option<(Option<usize>, Option<usize>)>,
+    /// The line last clicked on in the logs of each (thread, frame)
+    pub selected_lines: HashMap<(Option<usize>, Option<usize>), usize>,
+    /// The last diff with the next frame, so it's only redone when something changed
+    pub frame_diff: Option<FrameDiff>,
+}
+
+pub struct FrameDiff {
+    /// The (thread, frame) that was diffed with the frame after it
+    key: (usize, usize),
+    /// What `MapLogger::changes` was when this was made
+    changes: u64,
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Default)]
+pub struct LogSearch {
+    pub query: String,
+    /// Which match is the current one
+    pub cur_match: usize,
+    /// Set when the current match changes, so we only scroll to it once
+    pub scroll_to_match: bool,
 }
 
 impl MyApp {
     pub fn ui_logs(&mut self, ui: &mut Ui, _ctx: &egui::Context) {
-        let ui_state = &mut self.log_ui_state;
-        if let Some(Ok(state)) = &self.processed {
+        let ui_state = &mut self.doc.log_ui_state;
+        if let Some(Ok(state)) = &self.doc.processed {
             ui.horizontal(|ui| {
                 ui.label("Thread: ");
                 ComboBox::from_label(" ")
@@ -41,7 +81,38 @@ impl MyApp {
                             };
                         }
                     });
+                ui.label("thread id:");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut ui_state.thread_id_query).desired_width(100.0),
+                );
+                let submitted = response.lost_focus() && ui.input().key_pressed(egui::Key::Enter);
+                if ui.button("go").clicked() || submitted {
+                    if let Some(idx) = crate::thread_idx_for_id(state, &ui_state.thread_id_query) {
+                        ui_state.cur_thread = Some(idx);
+                        ui_state.cur_frame = None;
+                    }
+                }
+                let crashing_thread = state.requesting_thread;
+                if ui
+                    .add_enabled(
+                        crashing_thread.is_some(),
+                        egui::Button::new("go to crashing thread"),
+                    )
+                    .clicked()
+                {
+                    ui_state.cur_thread = crashing_thread;
+                    ui_state.cur_frame = None;
+                }
                 let thread = ui_state.cur_thread.and_then(|t| state.threads.get(t));
+                // The last frame has nothing to diff with
+                let has_next_frame = thread.is_some_and(|thread| {
+                    ui_state
+                        .cur_frame
+                        .is_some_and(|frame| frame + 1 < thread.frames.len())
+                });
+                if !has_next_frame {
+                    ui_state.diff_next_frame = false;
+                }
                 if let Some(thread) = thread {
                     ui.label("Frame: ");
                     ComboBox::from_label("")
@@ -67,22 +138,379 @@ impl MyApp {
                                 ui.selectable_value(&mut ui_state.cur_frame, Some(idx), name);
                             }
                         });
+                    if has_next_frame {
+                        ui.checkbox(&mut ui_state.diff_next_frame, "diff with next frame");
+                    }
+                    if ui.button("show in backtrace").clicked() {
+                        self.doc.tab = Tab::Processed;
+                        self.doc.processed_ui_state.cur_thread = ui_state.cur_thread.unwrap();
+                        self.doc.processed_ui_state.cur_frame = ui_state.cur_frame.unwrap_or(0);
+                    }
                 }
             });
         }
 
-        // Print the logs
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            let text = match (ui_state.cur_thread, ui_state.cur_frame) {
-                (Some(t), Some(f)) => self.logger.string_for_frame(t, f),
-                (Some(t), None) => self.logger.string_for_thread(t),
-                _ => self.logger.string_for_all(),
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut ui_state.show_all_fields, "show all fields");
+            ui.checkbox(&mut ui_state.show_timestamps, "show timestamps");
+            ui.checkbox(&mut ui_state.rich, "color by level");
+            ui.label("level:");
+            ComboBox::from_id_source("log level")
+                .selected_text(ui_state.level_threshold.to_string())
+                .show_ui(ui, |ui| {
+                    for level in [
+                        Level::TRACE,
+                        Level::DEBUG,
+                        Level::INFO,
+                        Level::WARN,
+                        Level::ERROR,
+                    ] {
+                        ui.selectable_value(
+                            &mut ui_state.level_threshold,
+                            level,
+                            level.to_string(),
+                        );
+                    }
+                });
+            let (cur_thread, cur_frame) = (ui_state.cur_thread, ui_state.cur_frame);
+            if ui.button("📋 copy logs").clicked() {
+                ui.output().copied_text = log_export(&self.doc.logger, cur_thread, cur_frame);
+            }
+            if ui.button("💾 save logs...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("txt", &["txt", "log"])
+                    .save_file()
+                {
+                    if let Err(e) =
+                        std::fs::write(&path, log_export(&self.doc.logger, cur_thread, cur_frame))
+                    {
+                        tracing::error!("failed to write {}: {e}", path.display());
+                    }
+                }
+            }
+        });
+        let logger = &self.doc.logger;
+        logger.set_show_all_fields(ui_state.show_all_fields);
+        logger.set_level_threshold(ui_state.level_threshold);
+        logger.set_show_timestamps(ui_state.show_timestamps);
+
+        // Diff the logs of two adjacent frames
+        if let (true, Some(t), Some(f)) = (
+            ui_state.diff_next_frame,
+            ui_state.cur_thread,
+            ui_state.cur_frame,
+        ) {
+            let changes = logger.changes();
+            let diff = match ui_state.frame_diff.take() {
+                Some(diff) if diff.key == (t, f) && diff.changes == changes => diff,
+                _ => FrameDiff {
+                    key: (t, f),
+                    changes,
+                    lines: diff_lines(
+                        &logger.string_for_frame(t, f),
+                        &logger.string_for_frame(t, f + 1),
+                    ),
+                },
             };
-            ui.add(
-                egui::TextEdit::multiline(&mut &**text)
-                    .font(TextStyle::Monospace)
-                    .desired_width(f32::INFINITY),
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for line in &diff.lines {
+                    let text =
+                        match line {
+                            DiffLine::Same(line) => RichText::new(format!("  {line}")),
+                            DiffLine::Removed(line) => RichText::new(format!("- {line}"))
+                                .color(Color32::from_rgb(200, 60, 60)),
+                            DiffLine::Added(line) => RichText::new(format!("+ {line}"))
+                                .color(Color32::from_rgb(60, 160, 60)),
+                        };
+                    ui.label(text.monospace());
+                }
+            });
+            ui_state.frame_diff = Some(diff);
+            return;
+        }
+
+        // Print the logs
+        let key = (ui_state.cur_thread, ui_state.cur_frame);
+        let (cur_thread, cur_frame) = key;
+        let switched = ui_state.shown_logs.replace(key) != Some(key);
+        let mut scroll_offset = ui_state
+            .scroll_offsets
+            .get(&key)
+            .copied()
+            .unwrap_or_default();
+        let mut selected_line = ui_state.selected_lines.get(&key).copied();
+        let mut search = std::mem::take(&mut ui_state.search);
+        if ui_state.rich {
+            self.ui_log_lines(
+                ui,
+                cur_thread,
+                cur_frame,
+                &mut search,
+                &mut scroll_offset,
+                switched,
+                &mut selected_line,
             );
+        } else {
+            self.ui_log_text(
+                ui,
+                cur_thread,
+                cur_frame,
+                Some(&mut search),
+                Some(&mut scroll_offset),
+                switched,
+            );
+        }
+        let ui_state = &mut self.doc.log_ui_state;
+        ui_state.search = search;
+        ui_state.scroll_offsets.insert(key, scroll_offset);
+        if let Some(line) = selected_line {
+            ui_state.selected_lines.insert(key, line);
+        }
+    }
+
+    /// Like `ui_log_text`, but each line is colored by the level of its message,
+    /// and clicking a line makes it the `selected_line`.
+    #[allow(clippy::too_many_arguments)]
+    fn ui_log_lines(
+        &self,
+        ui: &mut Ui,
+        cur_thread: Option<usize>,
+        cur_frame: Option<usize>,
+        search: &mut LogSearch,
+        scroll_offset: &mut f32,
+        restore_scroll: bool,
+        selected_line: &mut Option<usize>,
+    ) {
+        let lines = self.doc.logger.lines_for(cur_thread, cur_frame);
+        // Every match as (line index, range in that line's text), in order
+        let matches = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, line)| {
+                search_matches(&line.text, &search.query)
+                    .into_iter()
+                    .map(move |found| (idx, found))
+            })
+            .collect::<Vec<_>>();
+        ui_log_search_bar(ui, search, matches.len());
+
+        let default_color = ui.visuals().text_color();
+        let weak_color = ui.visuals().weak_text_color();
+        let font_id = TextStyle::Monospace.resolve(ui.style());
+        let row_height = ui.text_style_height(&TextStyle::Monospace);
+        let mut area = egui::ScrollArea::vertical();
+        if restore_scroll {
+            area = area.vertical_scroll_offset(*scroll_offset);
+        }
+        if let (true, Some((line_idx, _))) = (search.scroll_to_match, matches.get(search.cur_match))
+        {
+            // All the rows are the same height, so center the match's line by hand
+            let row_pitch = row_height + ui.spacing().item_spacing.y;
+            let target = *line_idx as f32 * row_pitch - ui.available_height() / 2.0;
+            area = area.vertical_scroll_offset(target.max(0.0));
+        }
+        let output = area.show_rows(ui, row_height, lines.len(), |ui, range| {
+            for line_idx in range {
+                let line = &lines[line_idx];
+                let color = match line.level {
+                    Some(level) if level == Level::ERROR => Color32::from_rgb(220, 50, 50),
+                    Some(level) if level == Level::WARN => Color32::from_rgb(220, 160, 0),
+                    Some(level) if level == Level::DEBUG => Color32::from_rgb(90, 140, 210),
+                    Some(level) if level == Level::TRACE => weak_color,
+                    _ => default_color,
+                };
+                let normal = TextFormat {
+                    font_id: font_id.clone(),
+                    color,
+                    ..Default::default()
+                };
+                let mut job = LayoutJob::default();
+                job.append(&" ".repeat(line.depth * 4), 0.0, normal.clone());
+                let first_match = matches.partition_point(|(idx, _)| *idx < line_idx);
+                let line_matches = matches[first_match..]
+                    .iter()
+                    .take_while(|(idx, _)| *idx == line_idx);
+                let mut pos = 0;
+                for (match_idx, (_, found)) in (first_match..).zip(line_matches) {
+                    job.append(&line.text[pos..found.start], 0.0, normal.clone());
+                    let format = search_match_format(&normal, match_idx == search.cur_match);
+                    job.append(&line.text[found.clone()], 0.0, format);
+                    pos = found.end;
+                }
+                job.append(&line.text[pos..], 0.0, normal);
+                // Reserve a spot under the text for the selection's background
+                let background = ui.painter().add(Shape::Noop);
+                let response = ui.add(egui::Label::new(job).sense(Sense::click()));
+                if response.clicked() {
+                    *selected_line = Some(line_idx);
+                }
+                if *selected_line == Some(line_idx) {
+                    let fill = ui.visuals().selection.bg_fill;
+                    ui.painter()
+                        .set(background, Shape::rect_filled(response.rect, 0.0, fill));
+                }
+            }
+        });
+        *scroll_offset = output.state.offset.y;
+        search.scroll_to_match = false;
+    }
+
+    /// Prints the logs for the given thread/frame, shared with the processed view's logs panel.
+    ///
+    /// If `search` is given, a search bar is shown and its matches are highlighted.
+    /// If `scroll_offset` is given, it's updated with where the logs are scrolled to,
+    /// and with `restore_scroll` they're first scrolled back to it.
+    pub fn ui_log_text(
+        &self,
+        ui: &mut Ui,
+        cur_thread: Option<usize>,
+        cur_frame: Option<usize>,
+        search: Option<&mut LogSearch>,
+        scroll_offset: Option<&mut f32>,
+        restore_scroll: bool,
+    ) {
+        let text = match (cur_thread, cur_frame) {
+            (Some(t), Some(f)) => self.doc.logger.string_for_frame(t, f),
+            (Some(t), None) => self.doc.logger.string_for_thread(t),
+            _ => self.doc.logger.string_for_all(),
+        };
+
+        let mut area = egui::ScrollArea::vertical();
+        if let (Some(offset), true) = (&scroll_offset, restore_scroll) {
+            area = area.vertical_scroll_offset(**offset);
+        }
+
+        let Some(search) = search else {
+            let scroll_output = area.show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut &**text)
+                        .font(TextStyle::Monospace)
+                        .desired_width(f32::INFINITY),
+                );
+            });
+            if let Some(offset) = scroll_offset {
+                *offset = scroll_output.state.offset.y;
+            }
+            return;
+        };
+
+        let matches = search_matches(&text, &search.query);
+        ui_log_search_bar(ui, search, matches.len());
+
+        let mut layouter = |ui: &Ui, text: &str, wrap_width: f32| {
+            let normal = TextFormat {
+                font_id: TextStyle::Monospace.resolve(ui.style()),
+                color: ui.visuals().text_color(),
+                ..Default::default()
+            };
+
+            let mut job = LayoutJob::default();
+            let mut pos = 0;
+            for (idx, found) in matches.iter().enumerate() {
+                job.append(&text[pos..found.start], 0.0, normal.clone());
+                let format = search_match_format(&normal, idx == search.cur_match);
+                job.append(&text[found.clone()], 0.0, format);
+                pos = found.end;
+            }
+            job.append(&text[pos..], 0.0, normal);
+            job.wrap_width = wrap_width;
+            ui.fonts().layout_job(job)
+        };
+
+        let scroll_output = area.show(ui, |ui| {
+            let output = egui::TextEdit::multiline(&mut &**text)
+                .desired_width(f32::INFINITY)
+                .layouter(&mut layouter)
+                .show(ui);
+            if let (true, Some(found)) = (search.scroll_to_match, matches.get(search.cur_match)) {
+                let char_idx = text[..found.start].chars().count();
+                let rect = output
+                    .galley
+                    .pos_from_ccursor(CCursor::new(char_idx))
+                    .translate(output.text_draw_pos.to_vec2());
+                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+            }
         });
+        if let Some(offset) = scroll_offset {
+            *offset = scroll_output.state.offset.y;
+        }
+        search.scroll_to_match = false;
     }
 }
+
+/// The search box and its previous/next buttons, given how many matches there are
+fn ui_log_search_bar(ui: &mut Ui, search: &mut LogSearch, match_count: usize) {
+    ui.horizontal(|ui| {
+        ui.label("search:");
+        let response = ui.text_edit_singleline(&mut search.query);
+        if response.changed() {
+            search.cur_match = 0;
+            search.scroll_to_match = true;
+        }
+        let submitted = response.lost_focus() && ui.input().key_pressed(egui::Key::Enter);
+        let prev = ui.add_enabled(match_count > 0, egui::Button::new("◀"));
+        let next = ui.add_enabled(match_count > 0, egui::Button::new("▶"));
+        if match_count > 0 {
+            if prev.clicked() {
+                search.cur_match = (search.cur_match + match_count - 1) % match_count;
+                search.scroll_to_match = true;
+            }
+            if next.clicked() || submitted {
+                search.cur_match = (search.cur_match + 1) % match_count;
+                search.scroll_to_match = true;
+            }
+            search.cur_match = search.cur_match.min(match_count - 1);
+            ui.label(format!("{}/{}", search.cur_match + 1, match_count));
+        } else if !search.query.is_empty() {
+            ui.label("no matches");
+        }
+    });
+}
+
+/// How a search match gets highlighted in text that's otherwise `normal`
+fn search_match_format(normal: &TextFormat, current: bool) -> TextFormat {
+    let background = if current {
+        Color32::from_rgb(255, 170, 0)
+    } else {
+        Color32::from_rgb(255, 240, 150)
+    };
+    TextFormat {
+        color: Color32::BLACK,
+        background,
+        ..normal.clone()
+    }
+}
+
+/// The logs for the given thread/frame, with a header saying which those are.
+fn log_export(logger: &MapLogger, cur_thread: Option<usize>, cur_frame: Option<usize>) -> String {
+    let (header, text) = match (cur_thread, cur_frame) {
+        (Some(t), Some(f)) => (
+            format!("Logs for thread {t}, frame {f}"),
+            logger.string_for_frame(t, f),
+        ),
+        (Some(t), None) => (format!("Logs for thread {t}"), logger.string_for_thread(t)),
+        _ => ("Logs for all threads".to_owned(), logger.string_for_all()),
+    };
+    format!("{header}\n{text}")
+}
+
+enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// A simple line-based diff, which is plenty for comparing the logs of two frames.
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    crate::diff_indices(&old, &new)
+        .into_iter()
+        .map(|indices| match indices {
+            (Some(i), Some(_)) => DiffLine::Same(old[i].to_owned()),
+            (Some(i), None) => DiffLine::Removed(old[i].to_owned()),
+            (None, Some(j)) => DiffLine::Added(new[j].to_owned()),
+            (None, None) => unreachable!("diffs always have at least one side"),
+        })
+        .collect()
+}
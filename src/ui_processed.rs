@@ -3,15 +3,98 @@
 use crate::processor::ProcessingStatus;
 use crate::{MyApp, Tab};
 use eframe::egui;
-use egui::{Color32, ComboBox, Context, FontId, Frame, ScrollArea, Ui};
+use egui::{Color32, ComboBox, Context, FontId, Frame, Label, ScrollArea, Sense, Ui};
 use egui_extras::{Size, TableBody, TableBuilder};
+use minidump::format::MINIDUMP_STREAM_TYPE;
+use minidump::system_info::PointerWidth;
+use minidump::{MinidumpMemoryInfoList, MinidumpMemoryList, MinidumpThreadList, Module};
 use minidump_common::utils::basename;
 use minidump_processor::ProcessState;
-use minidump_unwind::{CallStack, StackFrame};
+use minidump_unwind::{CallStack, FrameTrust, StackFrame};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
 pub struct ProcessedUiState {
     pub cur_thread: usize,
     pub cur_frame: usize,
+    pub show_return_address: bool,
+    pub thread_id_query: String,
+    /// Bumped to reset the backtrace's scroll position
+    pub backtrace_generation: u64,
+    pub search_query: String,
+    pub show_logs_panel: bool,
+    pub show_disasm_panel: bool,
+    /// The address typed into the "what's at this address?" tool
+    pub address_query: String,
+    /// The (thread, frame)s whose long signatures are shown in full
+    pub expanded_signatures: BTreeSet<(usize, usize)>,
+    /// Frames picked with ctrl/shift-click in the backtrace, for copying
+    pub selected_frames: BTreeSet<usize>,
+    /// Why the last "save JSON" failed, if it did
+    pub json_error: Option<String>,
+    /// Narrows the thread dropdown to threads whose name or id contains this
+    pub thread_filter: String,
+    /// Show every thread's backtrace instead of just the selected one
+    pub show_all_threads: bool,
+    /// Hide (real) frames that were found less reliably than this
+    pub min_trust: FrameTrust,
+    /// Only list the modules whose symbols couldn't be found
+    pub only_missing_symbols: bool,
+}
+
+/// Every trust level, from least to most reliable
+const TRUST_LEVELS: [FrameTrust; 7] = [
+    FrameTrust::None,
+    FrameTrust::Scan,
+    FrameTrust::CfiScan,
+    FrameTrust::FramePointer,
+    FrameTrust::CallFrameInfo,
+    FrameTrust::PreWalked,
+    FrameTrust::Context,
+];
+
+fn trust_rank(trust: FrameTrust) -> usize {
+    TRUST_LEVELS
+        .iter()
+        .position(|&level| level == trust)
+        .unwrap_or(0)
+}
+
+fn trust_name(trust: FrameTrust) -> &'static str {
+    match trust {
+        FrameTrust::None => "none",
+        FrameTrust::Scan => "scan",
+        FrameTrust::CfiScan => "cfi scan",
+        FrameTrust::FramePointer => "frame pointer",
+        FrameTrust::CallFrameInfo => "cfi",
+        FrameTrust::PreWalked => "prewalked",
+        FrameTrust::Context => "context",
+    }
+}
+
+fn trust_description(trust: FrameTrust) -> &'static str {
+    match trust {
+        FrameTrust::None => "unknown how this frame was found",
+        FrameTrust::Scan => {
+            "found by scanning the stack for things that look like return addresses (often bogus)"
+        }
+        FrameTrust::CfiScan => "found by stack scanning, but checked against unwind info",
+        FrameTrust::FramePointer => "found by following the frame pointer (usually right)",
+        FrameTrust::CallFrameInfo => "found using the unwind info (CFI) in the symbols (reliable)",
+        FrameTrust::PreWalked => "given to us already walked by whoever wrote the minidump",
+        FrameTrust::Context => "taken straight from the thread's saved registers (exact)",
+    }
+}
+
+fn trust_legend() -> String {
+    TRUST_LEVELS
+        .iter()
+        .rev()
+        .map(|&trust| format!("{}: {}", trust_name(trust), trust_description(trust)))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 use inline_shim::*;
@@ -46,12 +129,12 @@ mod inline_shim {
 
 impl MyApp {
     pub fn ui_processed(&mut self, ui: &mut Ui, ctx: &egui::Context) {
-        if let Some(Err(e)) = &self.minidump {
+        if let Some(Err(e)) = &self.doc.minidump {
             ui.label("Minidump couldn't be read!");
             ui.label(e.to_string());
             return;
         }
-        if let Some(state) = &self.processed {
+        if let Some(state) = &self.doc.processed {
             match state {
                 Ok(state) => {
                     self.ui_processed_good(ui, ctx, &state.clone());
@@ -65,7 +148,7 @@ impl MyApp {
     }
 
     fn ui_processed_good(&mut self, ui: &mut Ui, ctx: &Context, state: &ProcessState) {
-        // let is_symbolicated = self.cur_status == ProcessingStatus::Done;
+        // let is_symbolicated = self.doc.cur_status == ProcessingStatus::Done;
         egui::TopBottomPanel::top("info")
             .resizable(true)
             .default_height((ui.available_height() / 2.0).round())
@@ -78,7 +161,7 @@ impl MyApp {
             .show_inside(ui, |ui| {
                 ui.add_space(2.0);
                 ui.horizontal(|ui| {
-                    let stats = self.analysis_state.stats.lock().unwrap();
+                    let stats = self.doc.analysis_state.stats.lock().unwrap();
                     let symbols = stats.pending_symbols.lock().unwrap().clone();
                     let (t_done, t_todo) = stats.processor_stats.get_thread_count();
                     let frames_walked = stats.processor_stats.get_frame_count();
@@ -91,13 +174,19 @@ impl MyApp {
                             frames_walked as f32 / (t_todo as f32 * estimated_frames_per_thread);
                         ratio.min(0.9)
                     };
-                    let in_progress = self.cur_status < ProcessingStatus::Done;
+                    let in_progress = self.doc.cur_status < ProcessingStatus::Done;
                     let progress = if in_progress { estimated_progress } else { 1.0 };
 
                     ui.label(format!(
                         "fetching symbols {}/{}",
                         symbols.symbols_processed, symbols.symbols_requested
                     ));
+                    if in_progress {
+                        // Several lookups can be in flight, just show the newest one
+                        if let Some(module) = stats.fetching_symbols.lock().unwrap().last() {
+                            ui.label(format!("({module})"));
+                        }
+                    }
                     ui.label(format!("processing threads {t_done}/{t_todo}"));
                     ui.label(format!("frames walked {frames_walked}"));
 
@@ -108,13 +197,178 @@ impl MyApp {
                     ui.add(progress_bar);
                 });
             });
+        if self.doc.processed_ui_state.show_disasm_panel {
+            egui::TopBottomPanel::bottom("disasm panel")
+                .resizable(true)
+                .default_height((ui.available_height() / 4.0).round())
+                .frame(Frame::none())
+                .show_inside(ui, |ui| {
+                    ui.separator();
+                    self.ui_disasm(ui, state);
+                });
+        }
+        if self.doc.processed_ui_state.show_logs_panel {
+            egui::TopBottomPanel::bottom("logs panel")
+                .resizable(true)
+                .default_height((ui.available_height() / 4.0).round())
+                .frame(Frame::none())
+                .show_inside(ui, |ui| {
+                    ui.separator();
+                    self.ui_log_text(
+                        ui,
+                        Some(self.doc.processed_ui_state.cur_thread),
+                        Some(self.doc.processed_ui_state.cur_frame),
+                        None,
+                        None,
+                        false,
+                    );
+                });
+        }
         egui::CentralPanel::default()
             .frame(Frame::none())
             .show_inside(ui, |ui| {
                 ui.separator();
+                let (symbol_warnings, symbols_skipped, offline, cut_short) = {
+                    let stats = self.doc.analysis_state.stats.lock().unwrap();
+                    let warnings = stats.symbol_warnings.lock().unwrap().clone();
+                    let cut_short = stats.walk_cut_short.then_some(stats.max_frames).flatten();
+                    (warnings, stats.symbols_skipped, stats.offline, cut_short)
+                };
+                if let Some(max_frames) = cut_short {
+                    ui.colored_label(
+                        Color32::from_rgb(220, 140, 0),
+                        format!("⚠ a stack ran past {max_frames} frames, so walking stopped there. Threads it hadn't got to only have their first frame"),
+                    );
+                }
+                if symbols_skipped {
+                    ui.colored_label(
+                        Color32::from_rgb(220, 140, 0),
+                        "⚠ symbols were skipped, so frames are only module + offset",
+                    );
+                }
+                if offline {
+                    ui.label("offline: symbols were only loaded from disk");
+                }
+                for warning in symbol_warnings {
+                    ui.colored_label(Color32::from_rgb(220, 140, 0), format!("⚠ {warning}"));
+                }
+                if !cfg!(feature = "inline") {
+                    ui.label("Inline frame expansion is disabled in this build.");
+                }
+                ui.horizontal(|ui| {
+                    ui.checkbox(
+                        &mut self.doc.processed_ui_state.show_return_address,
+                        "show return addresses",
+                    );
+                    ui.checkbox(
+                        &mut self.doc.processed_ui_state.show_logs_panel,
+                        "show logs",
+                    );
+                    ui.checkbox(
+                        &mut self.doc.processed_ui_state.show_disasm_panel,
+                        "show disassembly",
+                    );
+                    ui.checkbox(&mut self.settings.full_module_paths, "full module paths");
+                    ui.checkbox(&mut self.settings.demangle_names, "demangle names");
+                    ui.label("min trust");
+                    ComboBox::from_id_source("min_trust")
+                        .selected_text(trust_name(self.doc.processed_ui_state.min_trust))
+                        .show_ui(ui, |ui| {
+                            for trust in TRUST_LEVELS {
+                                ui.selectable_value(
+                                    &mut self.doc.processed_ui_state.min_trust,
+                                    trust,
+                                    trust_name(trust),
+                                )
+                                .on_hover_text(trust_description(trust));
+                            }
+                        })
+                        .response
+                        .on_hover_text("hide frames that were found less reliably than this");
+                    let stack = state.threads.get(self.doc.processed_ui_state.cur_thread);
+                    let has_selection = !self.doc.processed_ui_state.selected_frames.is_empty();
+                    if ui
+                        .add_enabled(has_selection, egui::Button::new("📋 copy selected frames"))
+                        .on_hover_text("ctrl-click or shift-click frame numbers to select them")
+                        .clicked()
+                    {
+                        if let Some(stack) = stack {
+                            ui.output().copied_text = selected_frames_text(
+                                stack,
+                                &self.doc.processed_ui_state.selected_frames,
+                            );
+                        }
+                    }
+                });
 
-                if let Some(stack) = state.threads.get(self.processed_ui_state.cur_thread) {
-                    self.ui_processed_backtrace(ui, ctx, stack);
+                ui.horizontal(|ui| {
+                    if ui.button("📋 copy report").clicked() {
+                        ui.output().copied_text = crash_report(state);
+                    }
+                    if ui
+                        .button("📋 copy summary")
+                        .on_hover_text("a one line summary of the crash, for triage")
+                        .clicked()
+                    {
+                        ui.output().copied_text = crash_summary(state);
+                    }
+                    if ui.button("💾 save report...").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("txt", &["txt"])
+                            .save_file()
+                        {
+                            if let Err(e) = std::fs::write(&path, crash_report(state)) {
+                                tracing::error!("failed to write {}: {e}", path.display());
+                            }
+                        }
+                    }
+                    if ui.button("💾 save JSON...").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("json", &["json"])
+                            .save_file()
+                        {
+                            self.doc.processed_ui_state.json_error = save_json(state, &path).err();
+                        }
+                    }
+                    if let Some(e) = &self.doc.processed_ui_state.json_error {
+                        ui.colored_label(
+                            Color32::from_rgb(200, 60, 60),
+                            format!("failed to save JSON: {e}"),
+                        );
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(
+                        &mut self.doc.processed_ui_state.show_all_threads,
+                        false,
+                        "selected thread",
+                    );
+                    ui.radio_value(
+                        &mut self.doc.processed_ui_state.show_all_threads,
+                        true,
+                        "all threads",
+                    );
+                });
+
+                if self.doc.processed_ui_state.show_all_threads {
+                    ScrollArea::vertical().show(ui, |ui| {
+                        for (thread_idx, stack) in state.threads.iter().enumerate() {
+                            egui::CollapsingHeader::new(thread_label(state, thread_idx, stack))
+                                .id_source(thread_idx)
+                                .default_open(state.requesting_thread == Some(thread_idx))
+                                .show(ui, |ui| {
+                                    self.ui_processed_backtrace(ui, ctx, state, thread_idx, stack);
+                                });
+                        }
+                    });
+                } else if let Some(stack) =
+                    state.threads.get(self.doc.processed_ui_state.cur_thread)
+                {
+                    let thread_idx = self.doc.processed_ui_state.cur_thread;
+                    ui.push_id(self.doc.processed_ui_state.backtrace_generation, |ui| {
+                        self.ui_processed_backtrace(ui, ctx, state, thread_idx, stack);
+                    });
                 }
             });
     }
@@ -122,7 +376,7 @@ impl MyApp {
     fn ui_processed_data(&mut self, ui: &mut Ui, ctx: &Context, state: &ProcessState) {
         let cur_threadname = state
             .threads
-            .get(self.processed_ui_state.cur_thread)
+            .get(self.doc.processed_ui_state.cur_thread)
             .map(crate::threadname)
             .unwrap_or_default();
         egui::SidePanel::left("overall info")
@@ -152,8 +406,22 @@ impl MyApp {
                                 "CPU info".to_owned(),
                                 state.system_info.cpu_info.clone().unwrap_or_default(),
                             ),
-                            // ("Process Create Time".to_owned(), state.process_create_time.map(|s| format!("{:?}", s)).unwrap_or_default()),
-                            // ("Process Crash Time".to_owned(), format!("{:?}", state.time)),
+                            (
+                                "Process Create Time".to_owned(),
+                                state
+                                    .process_create_time
+                                    .map(format_time)
+                                    .unwrap_or_else(|| "<unknown>".to_owned()),
+                            ),
+                            ("Process Crash Time".to_owned(), format_time(state.time)),
+                            (
+                                "Process Uptime".to_owned(),
+                                state
+                                    .process_create_time
+                                    .and_then(|create| state.time.duration_since(create).ok())
+                                    .map(format_duration)
+                                    .unwrap_or_else(|| "<unknown>".to_owned()),
+                            ),
                             (
                                 "Crash Reason".to_owned(),
                                 state
@@ -175,8 +443,16 @@ impl MyApp {
                                 .unwrap_or_default(),
                             ),
                             ("Crashing Thread".to_owned(), cur_threadname.clone()),
+                            ("Detected Runtime".to_owned(), detect_runtimes(state)),
                         ],
                     );
+
+                    ui.add_space(20.0);
+                    self.ui_processed_search(ui, state);
+                    ui.add_space(20.0);
+                    self.ui_processed_address_lookup(ui, state);
+                    ui.add_space(20.0);
+                    self.ui_processed_modules(ui, state);
                 });
             });
         egui::CentralPanel::default()
@@ -184,52 +460,121 @@ impl MyApp {
             .show_inside(ui, |ui| {
                 ui.horizontal(|ui| {
                     ui.heading("Thread ");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.doc.processed_ui_state.thread_filter)
+                            .hint_text("filter")
+                            .desired_width(100.0),
+                    );
+                    let filter = self.doc.processed_ui_state.thread_filter.trim();
+                    let matching_threads = state
+                        .threads
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, stack)| {
+                            filter.is_empty()
+                                || stack
+                                    .thread_name
+                                    .as_deref()
+                                    .is_some_and(|name| name.contains(filter))
+                                || stack.thread_id.to_string().contains(filter)
+                        })
+                        .collect::<Vec<_>>();
+                    // Don't leave a thread selected that the filter is hiding
+                    if !matching_threads
+                        .iter()
+                        .any(|(idx, _)| *idx == self.doc.processed_ui_state.cur_thread)
+                    {
+                        if let Some((idx, _)) = matching_threads.first() {
+                            self.doc.processed_ui_state.cur_thread = *idx;
+                            self.doc.processed_ui_state.cur_frame = 0;
+                            self.doc.processed_ui_state.selected_frames.clear();
+                        }
+                    }
                     ComboBox::from_label("  ")
                         .width(400.0)
                         .selected_text(
                             state
                                 .threads
-                                .get(self.processed_ui_state.cur_thread)
-                                .map(crate::threadname)
+                                .get(self.doc.processed_ui_state.cur_thread)
+                                .map(|stack| {
+                                    thread_label(
+                                        state,
+                                        self.doc.processed_ui_state.cur_thread,
+                                        stack,
+                                    )
+                                })
                                 .unwrap_or_default(),
                         )
                         .show_ui(ui, |ui| {
-                            for (idx, stack) in state.threads.iter().enumerate() {
+                            for &(idx, stack) in &matching_threads {
                                 if ui
                                     .selectable_value(
-                                        &mut self.processed_ui_state.cur_thread,
+                                        &mut self.doc.processed_ui_state.cur_thread,
                                         idx,
-                                        crate::threadname(stack),
+                                        thread_label(state, idx, stack),
                                     )
                                     .changed()
                                 {
-                                    self.processed_ui_state.cur_frame = 0;
+                                    self.doc.processed_ui_state.cur_frame = 0;
+                                    self.doc.processed_ui_state.selected_frames.clear();
                                 };
                             }
                         });
+                    ui.label("thread id:");
+                    let response = ui.add(
+                        egui::TextEdit::singleline(
+                            &mut self.doc.processed_ui_state.thread_id_query,
+                        )
+                        .desired_width(100.0),
+                    );
+                    let submitted =
+                        response.lost_focus() && ui.input().key_pressed(egui::Key::Enter);
+                    if ui.button("go").clicked() || submitted {
+                        if let Some(idx) = crate::thread_idx_for_id(
+                            state,
+                            &self.doc.processed_ui_state.thread_id_query,
+                        ) {
+                            self.doc.processed_ui_state.cur_thread = idx;
+                            self.doc.processed_ui_state.cur_frame = 0;
+                            self.doc.processed_ui_state.selected_frames.clear();
+                        }
+                    }
                 });
                 ui.separator();
                 ScrollArea::vertical().show(ui, |ui| {
-                    if let Some(thread) = state.threads.get(self.processed_ui_state.cur_thread) {
+                    if let Some(thread) = state.threads.get(self.doc.processed_ui_state.cur_thread)
+                    {
                         crate::listing(
                             ui,
                             ctx,
                             2,
-                            [(
-                                "last_error_value".to_owned(),
-                                thread
-                                    .last_error_value
-                                    .map(|e| e.to_string())
-                                    .unwrap_or_default(),
-                            )],
+                            [
+                                (
+                                    "thread_id".to_owned(),
+                                    format!("{0} ({0:#x})", thread.thread_id),
+                                ),
+                                (
+                                    "last_error_value".to_owned(),
+                                    thread
+                                        .last_error_value
+                                        .map(|e| e.to_string())
+                                        .unwrap_or_default(),
+                                ),
+                            ],
                         );
-                        if let Some(frame) = thread.frames.get(self.processed_ui_state.cur_frame) {
+                        if let Some(frame) =
+                            thread.frames.get(self.doc.processed_ui_state.cur_frame)
+                        {
                             ui.add_space(20.0);
                             ui.horizontal(|ui| {
                                 use std::fmt::Write;
                                 let mut label = String::new();
-                                write!(&mut label, "{:02} - ", self.processed_ui_state.cur_frame)
-                                    .unwrap();
+                                write!(
+                                    &mut label,
+                                    "{:02} - ",
+                                    self.doc.processed_ui_state.cur_frame
+                                )
+                                .unwrap();
                                 crate::frame_signature(&mut label, frame).unwrap();
                                 ui.heading("Frame ");
 
@@ -241,45 +586,251 @@ impl MyApp {
                                             let mut label = String::new();
                                             write!(&mut label, "{idx:02} - ").unwrap();
                                             crate::frame_signature(&mut label, frame).unwrap();
+                                            // Only the list gets these, the selected text stays compact
+                                            if frame.source_file_name.is_some()
+                                                && frame.source_line.is_some()
+                                            {
+                                                label.push_str("  (");
+                                                crate::frame_source(&mut label, frame).unwrap();
+                                                label.push(')');
+                                            }
                                             ui.selectable_value(
-                                                &mut self.processed_ui_state.cur_frame,
+                                                &mut self.doc.processed_ui_state.cur_frame,
                                                 idx,
                                                 label,
                                             );
                                         }
                                     });
+
+                                // Step through the frames without reopening the dropdown
+                                let cur = self.doc.processed_ui_state.cur_frame;
+                                let last = thread.frames.len().saturating_sub(1);
+                                let steps = [
+                                    ("⏮", 0, cur > 0, "first frame"),
+                                    ("◀", cur.saturating_sub(1), cur > 0, "previous frame"),
+                                    ("▶", (cur + 1).min(last), cur < last, "next frame"),
+                                    ("⏭", last, cur < last, "last frame"),
+                                ];
+                                for (icon, target, enabled, hint) in steps {
+                                    if ui
+                                        .add_enabled(enabled, egui::Button::new(icon))
+                                        .on_hover_text(hint)
+                                        .clicked()
+                                    {
+                                        self.doc.processed_ui_state.cur_frame = target;
+                                    }
+                                }
                             });
 
-                            let regs = frame
-                                .context
-                                .valid_registers()
-                                .map(|(name, val)| (name.to_owned(), self.format_addr(val)));
-                            crate::listing(ui, ctx, 3, regs);
+                            if ui.button("📋 copy all registers").clicked() {
+                                let mut text = format!(
+                                    "thread {} frame {}\n",
+                                    thread.thread_id, self.doc.processed_ui_state.cur_frame
+                                );
+                                for (name, val) in frame.context.valid_registers() {
+                                    text.push_str(&format!("{name} = {}\n", self.format_addr(val)));
+                                }
+                                ui.output().copied_text = text;
+                            }
+                            let pointees = self.pointee_describer(state);
+                            let regs = frame.context.valid_registers().map(|(name, val)| {
+                                (name.to_owned(), self.format_addr(val), pointees(val))
+                            });
+                            crate::listing_with_hover(ui, ctx, 3, regs);
                         }
                     }
                 })
             });
     }
 
-    fn ui_processed_backtrace(&mut self, ui: &mut Ui, ctx: &Context, stack: &CallStack) {
+    fn ui_processed_modules(&mut self, ui: &mut Ui, state: &ProcessState) {
+        ui.heading("Modules");
+        ui.separator();
+        let missing = state
+            .modules
+            .iter()
+            .filter(|module| crate::module_has_symbols(state, module) == Some(false))
+            .count();
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{missing} of {} modules are missing symbols",
+                state.modules.iter().count()
+            ));
+            ui.checkbox(
+                &mut self.doc.processed_ui_state.only_missing_symbols,
+                "only show those",
+            );
+        });
+        let only_missing = self.doc.processed_ui_state.only_missing_symbols;
+        egui::Grid::new("processed_modules")
+            .striped(true)
+            .num_columns(5)
+            .show(ui, |ui| {
+                for module in state.modules.iter() {
+                    let has_symbols = crate::module_has_symbols(state, module);
+                    if only_missing && has_symbols != Some(false) {
+                        continue;
+                    }
+                    if ui
+                        .link(self.format_module_name(&module.name))
+                        .on_hover_text(module.debug_file().unwrap_or_default())
+                        .clicked()
+                    {
+                        self.show_module_in_raw_dump(&module.code_file());
+                    }
+                    match has_symbols {
+                        Some(true) => {
+                            ui.label("✓ symbols");
+                        }
+                        Some(false) => {
+                            ui.colored_label(Color32::from_rgb(220, 140, 0), "⚠ missing");
+                        }
+                        None => {
+                            ui.label("not needed")
+                                .on_hover_text("no stack went through this module");
+                        }
+                    }
+                    let debug_id = module
+                        .debug_identifier()
+                        .map(|id| id.breakpad().to_string())
+                        .unwrap_or_default();
+                    ui.monospace(&debug_id);
+                    if ui
+                        .add_enabled(!debug_id.is_empty(), egui::Button::new("📋"))
+                        .on_hover_text("copy debug id")
+                        .clicked()
+                    {
+                        ui.output().copied_text = debug_id.clone();
+                    }
+                    let overrides = &mut self.settings.symbol_overrides;
+                    let existing = overrides
+                        .iter()
+                        .position(|(id, _)| id.trim().eq_ignore_ascii_case(&debug_id));
+                    let hover = match existing {
+                        Some(idx) => format!("symbols overridden with {}", overrides[idx].1),
+                        None => "use a local symbol file for this module (reprocess to apply)"
+                            .to_owned(),
+                    };
+                    if ui
+                        .add_enabled(!debug_id.is_empty(), egui::Button::new("📂"))
+                        .on_hover_text(hover)
+                        .clicked()
+                    {
+                        if let Some(path) = crate::ui_settings::pick_symbol_override() {
+                            match existing {
+                                Some(idx) => overrides[idx].1 = path,
+                                None => overrides.push((debug_id, path)),
+                            }
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn ui_processed_search(&mut self, ui: &mut Ui, state: &ProcessState) {
+        ui.heading("Find in all threads");
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("function or module:");
+            ui.text_edit_singleline(&mut self.doc.processed_ui_state.search_query);
+        });
+
+        let query = self
+            .doc
+            .processed_ui_state
+            .search_query
+            .trim()
+            .to_lowercase();
+        if query.is_empty() {
+            return;
+        }
+        let mut hits = 0;
+        for (thread_idx, thread) in state.threads.iter().enumerate() {
+            for (frame_idx, frame) in thread.frames.iter().enumerate() {
+                let function_matches = frame
+                    .function_name
+                    .as_ref()
+                    .is_some_and(|name| crate::demangle(name).to_lowercase().contains(&query));
+                let module_matches = frame
+                    .module
+                    .as_ref()
+                    .is_some_and(|module| basename(&module.name).to_lowercase().contains(&query));
+                if !function_matches && !module_matches {
+                    continue;
+                }
+
+                hits += 1;
+                let mut label = format!("{} #{frame_idx}: ", crate::threadname(thread));
+                crate::frame_signature(&mut label, frame).unwrap();
+                if ui.link(label).clicked() {
+                    self.doc.processed_ui_state.cur_thread = thread_idx;
+                    self.doc.processed_ui_state.cur_frame = frame_idx;
+                    self.doc.processed_ui_state.selected_frames.clear();
+                }
+            }
+        }
+        if hits == 0 {
+            ui.label("no matches");
+        }
+    }
+
+    fn ui_processed_backtrace(
+        &mut self,
+        ui: &mut Ui,
+        ctx: &Context,
+        state: &ProcessState,
+        thread_idx: usize,
+        stack: &CallStack,
+    ) {
+        let crash_frame = if state.requesting_thread == Some(thread_idx) {
+            crash_frame_idx(state, stack)
+        } else {
+            None
+        };
         let font = egui::style::TextStyle::Body.resolve(ui.style());
-        TableBuilder::new(ui)
+        let show_return_address = self.doc.processed_ui_state.show_return_address;
+        let min_trust = trust_rank(self.doc.processed_ui_state.min_trust);
+        let hidden = stack
+            .frames
+            .iter()
+            .filter(|frame| trust_rank(frame.trust) < min_trust)
+            .count();
+        if hidden > 0 {
+            ui.label(format!(
+                "{hidden} frame(s) below \"{}\" trust hidden (frame numbers skip over them)",
+                trust_name(self.doc.processed_ui_state.min_trust)
+            ));
+        }
+        let addr_column = self.addr_column(ui, &font);
+        let mut table = TableBuilder::new(ui)
             .striped(true)
             .cell_layout(egui::Layout::left_to_right().with_cross_align(egui::Align::Center))
             .column(Size::initial(60.0).at_least(40.0))
-            .column(Size::initial(80.0).at_least(40.0))
+            .column(Size::initial(80.0).at_least(40.0));
+        if show_return_address {
+            table = table.column(addr_column);
+        }
+        table
             .column(Size::initial(160.0).at_least(40.0))
             .column(Size::initial(160.0).at_least(40.0))
             .column(Size::remainder().at_least(60.0))
             .resizable(true)
             .clip(false)
+            // In all-threads mode the whole list of backtraces scrolls instead
+            .scroll(!self.doc.processed_ui_state.show_all_threads)
             .header(20.0, |mut header| {
                 header.col(|ui| {
                     ui.heading("Frame");
                 });
                 header.col(|ui| {
-                    ui.heading("Trust");
+                    ui.heading("Trust").on_hover_text(trust_legend());
                 });
+                if show_return_address {
+                    header.col(|ui| {
+                        ui.heading("Return Address");
+                    });
+                }
                 header.col(|ui| {
                     ui.heading("Module");
                 });
@@ -291,21 +842,29 @@ impl MyApp {
                 });
             })
             .body(|mut body| {
-                let mut frame_count = 0;
-                let mut widths = [0.0f32; 5];
-                widths.clone_from_slice(body.widths());
+                let widths = body.widths().to_vec();
+                // Real frames are numbered by their index (so builds with and without
+                // inline frames agree), and the frames inlined into frame N are N.1, N.2...
                 for (frame_idx, frame) in stack.frames.iter().enumerate() {
-                    for inline in get_inline_frames(frame).iter().rev() {
-                        let frame_num = frame_count;
-                        frame_count += 1;
+                    if trust_rank(frame.trust) < min_trust {
+                        continue;
+                    }
+                    for (inline_idx, inline) in get_inline_frames(frame).iter().rev().enumerate() {
+                        let frame_num = format!("{frame_idx}.{}", inline_idx + 1);
                         self.ui_inline_frame(
-                            &mut body, ctx, &widths, &font, frame_num, frame, inline,
+                            &mut body, ctx, &widths, &font, &frame_num, frame, inline,
                         );
                     }
-
-                    let frame_num = frame_count;
-                    frame_count += 1;
-                    self.ui_real_frame(&mut body, ctx, &widths, &font, frame_idx, frame_num, frame);
+                    self.ui_real_frame(
+                        &mut body,
+                        ctx,
+                        &widths,
+                        &font,
+                        thread_idx,
+                        frame_idx,
+                        frame,
+                        crash_frame == Some(frame_idx),
+                    );
                 }
             });
     }
@@ -316,55 +875,100 @@ impl MyApp {
         ctx: &Context,
         widths: &[f32],
         font: &FontId,
+        thread_idx: usize,
         frame_idx: usize,
-        frame_num: usize,
         frame: &StackFrame,
+        is_crash_frame: bool,
     ) {
+        let show_return_address = self.doc.processed_ui_state.show_return_address;
+        let col_offset = usize::from(show_return_address);
         let col1_width = widths[0];
         let col2_width = widths[1];
-        let col3_width = widths[2];
-        let col4_width = widths[3];
-        let col5_width = widths[4];
+        let col3_width = widths[2 + col_offset];
+        let col4_width = widths[3 + col_offset];
+        let col5_width = widths[4 + col_offset];
 
-        let (col1, col2, col3, col4, col5, row_height) = {
+        // Caller frames get their return address backed up into the call
+        // instruction before symbol lookup, so mark frames where that happened.
+        let adjusted_hint = (frame.instruction != frame.resume_address).then(|| {
+            format!(
+                "return address {} was adjusted to {} for symbol lookup",
+                self.format_addr(frame.resume_address),
+                self.format_addr(frame.instruction),
+            )
+        });
+
+        let mut source = String::new();
+        crate::frame_source(&mut source, frame).unwrap();
+        let mut signature = String::new();
+        crate::frame_signature(&mut signature, frame).unwrap();
+        let signature_truncated = !self
+            .doc
+            .processed_ui_state
+            .expanded_signatures
+            .contains(&(thread_idx, frame_idx));
+        let copy_items = [
+            ("Copy signature", signature.clone()),
+            ("Copy source location", source.clone()),
+            (
+                "Copy instruction address",
+                self.format_addr(frame.instruction),
+            ),
+        ];
+        let context_menu = |ui: &mut Ui| {
+            for (label, text) in &copy_items {
+                if ui.button(*label).clicked() {
+                    ui.output().copied_text = text.clone();
+                    ui.close_menu();
+                }
+            }
+        };
+
+        let (col1, col2, ret_col, col3, col4, col5, row_height) = {
+            let text_color = ctx.style().visuals.text_color();
             let fonts = ctx.fonts();
             let col1 = {
+                let mut label = frame_idx.to_string();
+                if adjusted_hint.is_some() {
+                    label.push('*');
+                }
+                let inline_count = get_inline_frames(frame).len();
+                if inline_count > 0 {
+                    label.push_str(&format!(" +{inline_count}"));
+                }
+                fonts.layout(label, font.clone(), text_color, col1_width)
+            };
+            let col2 = fonts.layout(
+                trust_name(frame.trust).to_owned(),
+                font.clone(),
+                text_color,
+                col2_width,
+            );
+            let ret_col = show_return_address.then(|| {
                 fonts.layout(
-                    frame_num.to_string(),
+                    self.format_addr(frame.resume_address),
                     font.clone(),
-                    Color32::BLACK,
-                    col1_width,
+                    text_color,
+                    widths[2],
                 )
-            };
-            let col2 = {
-                let trust = match frame.trust {
-                    minidump_unwind::FrameTrust::None => "none",
-                    minidump_unwind::FrameTrust::Scan => "scan",
-                    minidump_unwind::FrameTrust::CfiScan => "cfi scan",
-                    minidump_unwind::FrameTrust::FramePointer => "frame pointer",
-                    minidump_unwind::FrameTrust::CallFrameInfo => "cfi",
-                    minidump_unwind::FrameTrust::PreWalked => "prewalked",
-                    minidump_unwind::FrameTrust::Context => "context",
-                };
-                fonts.layout(trust.to_owned(), font.clone(), Color32::BLACK, col2_width)
-            };
+            });
             let col3 = {
                 let label = if let Some(module) = &frame.module {
-                    basename(&module.name).to_string()
+                    self.format_module_name(&module.name)
                 } else {
                     String::new()
                 };
-                fonts.layout(label, font.clone(), Color32::BLACK, col3_width)
-            };
-            let col4 = {
-                let mut label = String::new();
-                crate::frame_source(&mut label, frame).unwrap();
-                fonts.layout(label, font.clone(), Color32::BLACK, col4_width)
+                fonts.layout(label, font.clone(), text_color, col3_width)
             };
-            let col5 = {
-                let mut label = String::new();
-                crate::frame_signature(&mut label, frame).unwrap();
-                fonts.layout(label, font.clone(), Color32::BLACK, col5_width)
+            let col4 = fonts.layout(source, font.clone(), text_color, col4_width);
+            // Long (usually templated) signatures get cut down to one line unless
+            // they've been expanded, in which case they wrap over as many as needed
+            let max_chars = (col5_width / fonts.glyph_width(font, 'x')).max(8.0) as usize;
+            let col5 = if signature_truncated && signature.chars().count() > max_chars {
+                let short = signature.chars().take(max_chars - 1).collect::<String>();
+                fonts.layout_no_wrap(format!("{short}…"), font.clone(), text_color)
+            } else {
+                fonts.layout(signature, font.clone(), text_color, col5_width)
             };
 
             let row_height = col1
@@ -374,77 +978,314 @@ impl MyApp {
                 .max(col3.rect.height())
                 .max(col4.rect.height())
                 .max(col5.rect.height())
+                .max(
+                    ret_col
+                        .as_ref()
+                        .map(|c| c.rect.height())
+                        .unwrap_or_default(),
+                )
                 + 6.0;
-            (col1, col2, col3, col4, col5, row_height)
+            (col1, col2, ret_col, col3, col4, col5, row_height)
         };
 
+        let is_selected = thread_idx == self.doc.processed_ui_state.cur_thread
+            && self
+                .doc
+                .processed_ui_state
+                .selected_frames
+                .contains(&frame_idx);
         body.row(row_height, |mut row| {
             row.col(|ui| {
+                if is_crash_frame {
+                    highlight_crash_cell(ui);
+                }
+                if is_selected {
+                    let rect = ui.max_rect();
+                    ui.painter()
+                        .rect_filled(rect, 0.0, ui.visuals().selection.bg_fill);
+                }
                 ui.centered_and_justified(|ui| {
-                    if ui.link(col1).clicked() {
-                        self.processed_ui_state.cur_frame = frame_idx;
+                    let mut response = ui.link(col1).context_menu(&context_menu);
+                    if let Some(hint) = adjusted_hint {
+                        response = response.on_hover_text(hint);
+                    }
+                    if response.clicked() {
+                        let modifiers = ui.input().modifiers;
+                        self.select_frame(thread_idx, frame_idx, modifiers);
                     }
                 });
             });
             row.col(|ui| {
+                if is_crash_frame {
+                    highlight_crash_cell(ui);
+                }
                 ui.centered_and_justified(|ui| {
-                    if ui.link(col2).clicked() {
-                        self.tab = Tab::Logs;
-                        self.log_ui_state.cur_thread = Some(self.processed_ui_state.cur_thread);
-                        self.log_ui_state.cur_frame = Some(frame_idx);
+                    let response = ui
+                        .link(col2)
+                        .on_hover_text(trust_description(frame.trust))
+                        .context_menu(&context_menu);
+                    if response.clicked() {
+                        self.doc.tab = Tab::Logs;
+                        self.doc.log_ui_state.cur_thread = Some(thread_idx);
+                        self.doc.log_ui_state.cur_frame = Some(frame_idx);
                     }
                 });
             });
+            if let Some(ret_col) = ret_col {
+                row.col(|ui| {
+                    if is_crash_frame {
+                        highlight_crash_cell(ui);
+                    }
+                    ui.centered_and_justified(|ui| {
+                        ui.add(Label::new(ret_col).sense(Sense::click()))
+                            .context_menu(&context_menu);
+                    });
+                });
+            }
             row.col(|ui| {
+                if is_crash_frame {
+                    highlight_crash_cell(ui);
+                }
                 ui.centered_and_justified(|ui| {
-                    ui.label(col3);
+                    if let Some(module) = &frame.module {
+                        if ui.link(col3).context_menu(&context_menu).clicked() {
+                            self.show_module_in_raw_dump(&module.code_file());
+                        }
+                    } else {
+                        ui.add(Label::new(col3).sense(Sense::click()))
+                            .context_menu(&context_menu);
+                    }
                 });
             });
             row.col(|ui| {
-                ui.label(col4);
+                if is_crash_frame {
+                    highlight_crash_cell(ui);
+                }
+                if let (Some(file), Some(line)) = (&frame.source_file_name, frame.source_line) {
+                    let hover = if self.settings.source_root.trim().is_empty() {
+                        "copy file:line (set a source root to open it instead)"
+                    } else {
+                        "open in editor"
+                    };
+                    let response = ui
+                        .link(col4)
+                        .on_hover_text(hover)
+                        .context_menu(&context_menu);
+                    if response.clicked() && !open_source(&self.settings, file, line) {
+                        ui.output().copied_text = format!("{file}:{line}");
+                    }
+                } else {
+                    ui.add(Label::new(col4).sense(Sense::click()))
+                        .context_menu(&context_menu);
+                }
             });
             row.col(|ui| {
-                ui.label(col5);
+                if is_crash_frame {
+                    highlight_crash_cell(ui);
+                }
+                let response = ui
+                    .add(Label::new(col5).sense(Sense::click()))
+                    .on_hover_text("click to expand or collapse long signatures")
+                    .context_menu(&context_menu);
+                if response.clicked() {
+                    let expanded = &mut self.doc.processed_ui_state.expanded_signatures;
+                    if !expanded.remove(&(thread_idx, frame_idx)) {
+                        expanded.insert((thread_idx, frame_idx));
+                    }
+                }
             });
         });
     }
 
+    fn show_module_in_raw_dump(&mut self, code_file: &str) {
+        let Some(Ok(dump)) = &self.doc.minidump else {
+            return;
+        };
+        let module_list = MINIDUMP_STREAM_TYPE::ModuleListStream as u32;
+        if let Some(idx) = dump
+            .all_streams()
+            .position(|stream| stream.stream_type == module_list)
+        {
+            self.doc.tab = Tab::RawDump;
+            self.doc.raw_dump_ui_state.cur_stream = idx + 1;
+            self.doc.raw_dump_ui_state.highlight_module = Some(basename(code_file).to_owned());
+            self.doc.raw_dump_ui_state.scroll_to_module = true;
+        }
+    }
+
+    /// Makes a function that says what a pointer-sized value points into, if anything.
+    ///
+    /// The streams are only parsed once so this can be called for every register.
+    fn pointee_describer<'a>(
+        &'a self,
+        state: &'a ProcessState,
+    ) -> impl Fn(u64) -> Option<String> + 'a {
+        let describer = self.address_describer(state);
+        move |addr| {
+            let description = describer(addr).into_iter().next()?;
+            Some(format!("→ {description}"))
+        }
+    }
+
+    /// Makes a function that lists everything we know about where an address is,
+    /// most specific first.
+    fn address_describer<'a>(
+        &'a self,
+        state: &'a ProcessState,
+    ) -> impl Fn(u64) -> Vec<String> + 'a {
+        let dump = match &self.doc.minidump {
+            Some(Ok(dump)) => Some(dump),
+            _ => None,
+        };
+        let threads = dump.and_then(|dump| dump.get_stream::<MinidumpThreadList>().ok());
+        let memory_info = dump.and_then(|dump| dump.get_stream::<MinidumpMemoryInfoList>().ok());
+        let memory = dump.and_then(|dump| dump.get_stream::<MinidumpMemoryList>().ok());
+
+        move |addr| {
+            let mut descriptions = vec![];
+            if let Some(module) = state.modules.module_at_address(addr) {
+                descriptions.push(format!(
+                    "inside {} +{:#x}",
+                    self.format_module_name(&module.code_file()),
+                    addr - module.base_address()
+                ));
+            }
+            if let Some(threads) = &threads {
+                for thread in &threads.threads {
+                    let stack = &thread.raw.stack;
+                    if crate::ui_raw_dump::region_contains(
+                        stack.start_of_memory_range,
+                        stack.memory.data_size as u64,
+                        addr,
+                    ) {
+                        descriptions.push(format!("stack of thread {}", thread.raw.thread_id));
+                    }
+                }
+            }
+            if let Some(info) = memory_info
+                .as_ref()
+                .and_then(|list| list.memory_info_at_address(addr))
+            {
+                descriptions.push(format!(
+                    "{:?} {:?} region at {}",
+                    info.state,
+                    info.protection,
+                    self.format_addr(info.raw.base_address)
+                ));
+            }
+            if let Some(region) = memory
+                .as_ref()
+                .and_then(|list| list.memory_at_address(addr))
+            {
+                descriptions.push(format!(
+                    "captured memory at {}",
+                    self.format_addr(region.base_address)
+                ));
+            }
+            descriptions
+        }
+    }
+
+    fn ui_processed_address_lookup(&mut self, ui: &mut Ui, state: &ProcessState) {
+        ui.heading("What's at this address?");
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("address:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.doc.processed_ui_state.address_query)
+                    .hint_text("0x..."),
+            );
+        });
+
+        let query = self.doc.processed_ui_state.address_query.trim();
+        if query.is_empty() {
+            return;
+        }
+        let hex = query
+            .strip_prefix("0x")
+            .or_else(|| query.strip_prefix("0X"))
+            .unwrap_or(query);
+        let addr = match u64::from_str_radix(hex, 16) {
+            Ok(addr) => addr,
+            Err(e) => {
+                ui.label(format!("not a hex address: {e}"));
+                return;
+            }
+        };
+        if matches!(self.doc.pointer_width, PointerWidth::Bits32) && addr > u64::from(u32::MAX) {
+            ui.label("too big for an address in a 32-bit process");
+            return;
+        }
+
+        let descriptions = self.address_describer(state)(addr);
+        ui.label(self.format_addr(addr));
+        if descriptions.is_empty() {
+            ui.label("isn't in any module, stack, or known memory region");
+        }
+        for description in descriptions {
+            ui.label(format!("→ {description}"));
+        }
+    }
+
+    fn select_frame(&mut self, thread_idx: usize, frame_idx: usize, modifiers: egui::Modifiers) {
+        let ui_state = &mut self.doc.processed_ui_state;
+        if thread_idx != ui_state.cur_thread {
+            // Selections don't span threads
+            ui_state.cur_thread = thread_idx;
+            ui_state.cur_frame = frame_idx;
+            ui_state.selected_frames.clear();
+        }
+        if modifiers.shift {
+            // Extend from the current frame, which stays as the anchor
+            let (lo, hi) = if ui_state.cur_frame <= frame_idx {
+                (ui_state.cur_frame, frame_idx)
+            } else {
+                (frame_idx, ui_state.cur_frame)
+            };
+            ui_state.selected_frames.extend(lo..=hi);
+        } else if modifiers.command {
+            if !ui_state.selected_frames.remove(&frame_idx) {
+                ui_state.selected_frames.insert(frame_idx);
+            }
+            ui_state.cur_frame = frame_idx;
+        } else {
+            ui_state.selected_frames.clear();
+            ui_state.cur_frame = frame_idx;
+        }
+    }
+
     fn ui_inline_frame(
         &mut self,
         body: &mut TableBody,
         ctx: &Context,
         widths: &[f32],
         font: &FontId,
-        frame_num: usize,
+        frame_num: &str,
         real_frame: &StackFrame,
         frame: &InlineFrame,
     ) {
+        let show_return_address = self.doc.processed_ui_state.show_return_address;
+        let col_offset = usize::from(show_return_address);
         let col1_width = widths[0];
         let col2_width = widths[1];
-        let col3_width = widths[2];
-        let col4_width = widths[3];
-        let col5_width = widths[4];
+        let col3_width = widths[2 + col_offset];
+        let col4_width = widths[3 + col_offset];
+        let col5_width = widths[4 + col_offset];
         let (col1, col2, col3, col4, col5, row_height) = {
+            let text_color = ctx.style().visuals.text_color();
             let fonts = ctx.fonts();
-            let col1 = {
-                fonts.layout(
-                    frame_num.to_string(),
-                    font.clone(),
-                    Color32::BLACK,
-                    col1_width,
-                )
-            };
+            let col1 = { fonts.layout(frame_num.to_owned(), font.clone(), text_color, col1_width) };
             let col2 = {
                 let trust = "inlined";
-                fonts.layout(trust.to_owned(), font.clone(), Color32::BLACK, col2_width)
+                fonts.layout(trust.to_owned(), font.clone(), text_color, col2_width)
             };
             let col3 = {
                 let label = if let Some(module) = &real_frame.module {
-                    basename(&module.name).to_string()
+                    self.format_module_name(&module.name)
                 } else {
                     String::new()
                 };
-                fonts.layout(label, font.clone(), Color32::BLACK, col3_width)
+                fonts.layout(label, font.clone(), text_color, col3_width)
             };
             let col4 = {
                 let label = if let (Some(source_file), Some(line)) =
@@ -454,11 +1295,11 @@ impl MyApp {
                 } else {
                     String::new()
                 };
-                fonts.layout(label, font.clone(), Color32::BLACK, col4_width)
+                fonts.layout(label, font.clone(), text_color, col4_width)
             };
             let col5 = {
-                let label = frame.function_name.clone();
-                fonts.layout(label, font.clone(), Color32::BLACK, col5_width)
+                let label = crate::demangle(&frame.function_name).into_owned();
+                fonts.layout(label, font.clone(), text_color, col5_width)
             };
 
             let row_height = col1
@@ -483,6 +1324,10 @@ impl MyApp {
                     ui.label(col2);
                 });
             });
+            if show_return_address {
+                // Inlined frames share their real frame's return address
+                row.col(|_ui| {});
+            }
             row.col(|ui| {
                 ui.centered_and_justified(|ui| {
                     ui.label(col3);
@@ -497,3 +1342,235 @@ impl MyApp {
         });
     }
 }
+
+/// A plain text version of the whole crash, for pasting into bug reports
+pub fn crash_report(state: &ProcessState) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    if let Some(info) = &state.exception_info {
+        writeln!(&mut out, "Crash reason:  {}", info.reason).unwrap();
+        let pointer_width = state.system_info.cpu.pointer_width();
+        writeln!(
+            &mut out,
+            "Crash address: {}",
+            crate::format_addr_for(pointer_width, info.address.0)
+        )
+        .unwrap();
+    } else {
+        writeln!(&mut out, "No crash").unwrap();
+    }
+    if let Some(assertion) = &state.assertion {
+        writeln!(&mut out, "Assertion: {assertion}").unwrap();
+    }
+
+    // The crashing thread goes first, the rest follow in their usual order
+    let crashing = state.requesting_thread;
+    let thread_order = crashing
+        .into_iter()
+        .chain((0..state.threads.len()).filter(|&idx| Some(idx) != crashing));
+    for thread_idx in thread_order {
+        let Some(stack) = state.threads.get(thread_idx) else {
+            continue;
+        };
+        out.push('\n');
+        write!(&mut out, "Thread {thread_idx} {}", crate::threadname(stack)).unwrap();
+        if Some(thread_idx) == crashing {
+            out.push_str(" (crashed)");
+        }
+        out.push('\n');
+        for (frame_idx, frame) in stack.frames.iter().enumerate() {
+            out.push_str("  ");
+            write_frame_line(&mut out, frame_idx, frame);
+        }
+    }
+    out
+}
+
+/// Finds the file a symbol file's source path refers to under `source_root`.
+///
+/// Symbols contain whatever path the file had on the build machine, so try the
+/// path relative to the root with fewer and fewer of its leading directories.
+fn local_source_path(source_root: &Path, file: &str) -> Option<PathBuf> {
+    let parts = file
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+    (0..parts.len())
+        .map(|start| source_root.join(parts[start..].join("/")))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Opens the given source location in the user's editor. Returns false if there's
+/// no local copy of the file, or no way to open it.
+fn open_source(settings: &crate::Settings, file: &str, line: u32) -> bool {
+    let source_root = settings.source_root.trim();
+    if source_root.is_empty() {
+        return false;
+    }
+    let Some(path) = local_source_path(Path::new(source_root), file) else {
+        tracing::warn!("couldn't find {file} under {source_root}");
+        return false;
+    };
+    let editor_url = settings.editor_url.trim();
+    let result = if editor_url.is_empty() {
+        opener::open(&path)
+    } else {
+        opener::open(
+            editor_url
+                .replace("{path}", &path.display().to_string())
+                .replace("{line}", &line.to_string()),
+        )
+    };
+    if let Err(e) = result {
+        tracing::error!("failed to open {}: {e}", path.display());
+        return false;
+    }
+    true
+}
+
+/// Something like `SIGSEGV @ 0x0 in libfoo.so!Bar::baz (thread main (12))`
+fn crash_summary(state: &ProcessState) -> String {
+    use std::fmt::Write;
+    let mut out = match &state.exception_info {
+        Some(info) => {
+            let pointer_width = state.system_info.cpu.pointer_width();
+            format!(
+                "{} @ {}",
+                info.reason,
+                crate::format_addr_for(pointer_width, info.address.0)
+            )
+        }
+        None => "<no crash reason>".to_owned(),
+    };
+    let stack = state
+        .requesting_thread
+        .and_then(|thread_idx| state.threads.get(thread_idx));
+    if let Some(stack) = stack {
+        if let Some(frame) = stack.frames.first() {
+            out.push_str(" in ");
+            if let (Some(module), Some(_)) = (&frame.module, &frame.function_name) {
+                write!(&mut out, "{}!", basename(&module.code_file())).unwrap();
+            }
+            crate::frame_signature(&mut out, frame).unwrap();
+        }
+        write!(&mut out, " (thread {})", crate::threadname(stack)).unwrap();
+    }
+    out
+}
+
+fn thread_label(state: &ProcessState, thread_idx: usize, stack: &CallStack) -> String {
+    if state.requesting_thread == Some(thread_idx) {
+        format!("⚠ {}", crate::threadname(stack))
+    } else {
+        crate::threadname(stack)
+    }
+}
+
+/// The frame of the crashing thread that actually faulted: the one whose
+/// instruction is the crash address, or else the frame from the crash context.
+fn crash_frame_idx(state: &ProcessState, stack: &CallStack) -> Option<usize> {
+    let crash_address = state.exception_info.as_ref()?.address.0;
+    stack
+        .frames
+        .iter()
+        .position(|frame| frame.instruction == crash_address)
+        .or_else(|| {
+            let first = stack.frames.first()?;
+            (first.trust == minidump_unwind::FrameTrust::Context).then_some(0)
+        })
+}
+
+fn highlight_crash_cell(ui: &mut Ui) {
+    let rect = ui.max_rect();
+    let color = if ui.visuals().dark_mode {
+        Color32::from_rgb(110, 30, 30)
+    } else {
+        Color32::from_rgb(255, 210, 210)
+    };
+    ui.painter().rect_filled(rect, 0.0, color);
+}
+
+/// One line per selected frame, in the same shape as the backtrace
+fn selected_frames_text(stack: &CallStack, selected: &BTreeSet<usize>) -> String {
+    let mut out = String::new();
+    for &frame_idx in selected {
+        if let Some(frame) = stack.frames.get(frame_idx) {
+            write_frame_line(&mut out, frame_idx, frame);
+        }
+    }
+    out
+}
+
+/// Writes the same JSON that `minidump-stackwalk --json` produces
+fn save_json(state: &ProcessState, path: &std::path::Path) -> Result<(), String> {
+    let mut json = Vec::new();
+    state
+        .print_json(&mut json, true)
+        .map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+fn write_frame_line(out: &mut String, frame_idx: usize, frame: &StackFrame) {
+    use std::fmt::Write;
+    write!(out, "{frame_idx:02} - ").unwrap();
+    crate::frame_signature(out, frame).unwrap();
+    let mut source = String::new();
+    crate::frame_source(&mut source, frame).unwrap();
+    if !source.is_empty() {
+        write!(out, " ({source})").unwrap();
+    }
+    out.push('\n');
+}
+
+/// Guesses what languages/runtimes the process was using from the names of its modules
+fn detect_runtimes(state: &ProcessState) -> String {
+    const RUNTIMES: &[(&[&str], &str)] = &[
+        (&["libstdc++"], "C++ (libstdc++)"),
+        (&["libc++"], "C++ (libc++)"),
+        (&["msvcp", "vcruntime", "msvcr", "ucrtbase"], "MSVC runtime"),
+        (&["corefoundation"], "CoreFoundation"),
+        (&["libswiftcore"], "Swift"),
+        (&["libjvm", "jvm.dll"], "Java (JVM)"),
+        (&["libart.so"], "Android ART"),
+        (&["coreclr", "clr.dll", "mscorwks"], ".NET"),
+        (&["libv8", "v8.dll", "libnode", "node.exe"], "V8"),
+        (&["libpython", "python3"], "Python"),
+        (&["xul"], "Gecko"),
+    ];
+
+    let module_names = state
+        .modules
+        .iter()
+        .map(|module| basename(&module.name).to_lowercase())
+        .collect::<Vec<_>>();
+    RUNTIMES
+        .iter()
+        .filter(|(patterns, _)| {
+            module_names
+                .iter()
+                .any(|name| patterns.iter().any(|pattern| name.contains(pattern)))
+        })
+        .map(|(_, runtime)| *runtime)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_time(time: SystemTime) -> String {
+    OffsetDateTime::from(time)
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| format!("{time:?}"))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, mins, secs) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if days > 0 {
+        format!("{days}d {hours}h {mins}m {secs}s")
+    } else if hours > 0 {
+        format!("{hours}h {mins}m {secs}s")
+    } else if mins > 0 {
+        format!("{mins}m {secs}s")
+    } else {
+        format!("{secs}s")
+    }
+}
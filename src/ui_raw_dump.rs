@@ -1,18 +1,75 @@
 use crate::MyApp;
 use eframe::egui;
-use egui::{Frame, TextStyle, Ui};
+use egui::{pos2, Color32, Frame, Rect, RichText, Sense, TextStyle, Ui, Vec2};
 use egui_extras::{Size, TableBuilder};
 use memmap2::Mmap;
-use minidump::{format::MINIDUMP_STREAM_TYPE, Minidump};
+use minidump::{format::MINIDUMP_STREAM_TYPE, Minidump, Module};
+use minidump_common::utils::basename;
 use num_traits::FromPrimitive;
+use std::sync::Arc;
 
 pub struct RawDumpUiState {
     pub cur_stream: usize,
+    pub show_raw_linux_streams: bool,
+    pub show_raw_crashpad_info: bool,
+    pub show_raw_mac_crash_info: bool,
+    /// Mask the values of environment variables that look like secrets
+    pub redact_environ: bool,
+    pub cur_memory_region: Option<usize>,
+    pub hex_offset: usize,
+    pub hex_jump: String,
+    /// The whole dump file, mapped on demand for the file bytes view
+    pub file_bytes: Option<Arc<Mmap>>,
+    pub maps_sort: RegionSort,
+    pub maps_sort_descending: bool,
+    pub memory_info_sort: RegionSort,
+    pub memory_info_sort_descending: bool,
+    /// Basename of the module to highlight in the module list
+    pub highlight_module: Option<String>,
+    /// Whether the module list still needs to scroll to `highlight_module`
+    pub scroll_to_module: bool,
 }
 
+/// How to order a table of memory regions
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RegionSort {
+    /// The order the entries appear in the stream
+    File,
+    Address,
+    Size,
+}
+
+/// One line of /proc/self/maps
+struct LinuxMapsEntry<'a> {
+    start: u64,
+    end: u64,
+    perms: &'a str,
+    offset: &'a str,
+    device: &'a str,
+    inode: &'a str,
+    path: &'a str,
+}
+
+/// One MINIDUMP_THREAD_EX, which the minidump crate doesn't parse for us
+struct ThreadEx {
+    thread_id: u32,
+    suspend_count: u32,
+    priority_class: u32,
+    priority: u32,
+    teb: u64,
+    stack_start: u64,
+    stack_size: u32,
+    context_size: u32,
+    backing_store_start: u64,
+    backing_store_size: u32,
+}
+
+/// The `cur_stream` value for viewing the raw bytes of the whole file
+pub const FILE_BYTES_VIEW: usize = usize::MAX;
+
 impl MyApp {
     pub fn ui_raw_dump(&mut self, ui: &mut Ui, _ctx: &egui::Context) {
-        if let Some(minidump) = &self.minidump {
+        if let Some(minidump) = &self.doc.minidump {
             match minidump {
                 Ok(dump) => {
                     self.ui_raw_dump_good(ui, &dump.clone());
@@ -32,43 +89,88 @@ impl MyApp {
                 self.ui_raw_dump_streams(ui, dump);
             });
         egui::CentralPanel::default().show_inside(ui, |ui| {
+            let raw_stream_type = self
+                .doc
+                .raw_dump_ui_state
+                .cur_stream
+                .checked_sub(1)
+                .and_then(|idx| dump.all_streams().nth(idx))
+                .map(|entry| entry.stream_type);
+            let stream = raw_stream_type.and_then(MINIDUMP_STREAM_TYPE::from_u32);
             egui::ScrollArea::vertical().show(ui, |ui| {
-                if self.raw_dump_ui_state.cur_stream == 0 {
+                if self.doc.raw_dump_ui_state.cur_stream == 0 {
                     self.ui_raw_dump_top_level(ui, dump);
                     return;
                 }
-                let stream = dump
-                    .all_streams()
-                    .nth(self.raw_dump_ui_state.cur_stream - 1)
-                    .and_then(|entry| MINIDUMP_STREAM_TYPE::from_u32(entry.stream_type));
+                if self.doc.raw_dump_ui_state.cur_stream == FILE_BYTES_VIEW {
+                    self.ui_raw_dump_file_bytes(ui);
+                    return;
+                }
+                if let (None, Some(raw_stream_type)) = (
+                    stream.filter(|&stream| stream_supported(stream)),
+                    raw_stream_type,
+                ) {
+                    // We don't know how to decode this one, so just show the bytes
+                    match dump.get_raw_stream(raw_stream_type) {
+                        Ok(bytes) => self.ui_raw_dump_memory_hex(ui, 0, bytes, None),
+                        Err(e) => {
+                            ui.label("Failed to read stream");
+                            ui.label(e.to_string());
+                        }
+                    }
+                    return;
+                }
                 if let Some(stream) = stream {
+                    let text = StreamText {
+                        dump,
+                        stream,
+                        brief: self.settings.raw_dump_brief,
+                        redact: self
+                            .doc
+                            .raw_dump_ui_state
+                            .redact_environ
+                            .then(|| self.sensitive_env_patterns()),
+                        text: Default::default(),
+                    };
+                    if ui.button("📋 copy").clicked() {
+                        match text.get() {
+                            Ok(text) => {
+                                ui.output().copied_text = format!("# {stream:?}\n{text}");
+                            }
+                            Err(e) => tracing::error!("failed to print {stream:?}: {e}"),
+                        }
+                    }
+
                     use MINIDUMP_STREAM_TYPE::*;
                     match stream {
-                        SystemInfoStream => self.update_raw_dump_system_info(ui, dump),
-                        ThreadNamesStream => self.update_raw_dump_thread_names(ui, dump),
-                        MiscInfoStream => self.update_raw_dump_misc_info(ui, dump),
-                        ThreadListStream => self.update_raw_dump_thread_list(ui, dump),
-                        AssertionInfoStream => self.update_raw_dump_assertion_info(ui, dump),
-                        BreakpadInfoStream => self.update_raw_dump_breakpad_info(ui, dump),
-                        CrashpadInfoStream => self.update_raw_dump_crashpad_info(ui, dump),
-                        ExceptionStream => self.update_raw_dump_exception(ui, dump),
-                        ModuleListStream => self.update_raw_dump_module_list(ui, dump),
+                        SystemInfoStream => self.update_raw_dump_system_info(ui, dump, &text),
+                        ThreadNamesStream => self.update_raw_dump_thread_names(ui, dump, &text),
+                        AssertionInfoStream => self.update_raw_dump_assertion_info(ui, dump, &text),
+                        BreakpadInfoStream => self.update_raw_dump_breakpad_info(ui, dump, &text),
                         UnloadedModuleListStream => {
-                            self.update_raw_dump_unloaded_module_list(ui, dump)
+                            self.update_raw_dump_unloaded_module_list(ui, dump, &text)
                         }
-                        MemoryListStream => self.update_raw_dump_memory_list(ui, dump),
-                        Memory64ListStream => self.update_raw_dump_memory_64_list(ui, dump),
-                        MemoryInfoListStream => self.update_raw_dump_memory_info_list(ui, dump),
-                        LinuxMaps => self.update_raw_dump_linux_maps(ui, dump),
-                        LinuxCmdLine => self.update_raw_dump_linux_cmd_line(ui, dump),
-                        LinuxCpuInfo => self.update_raw_dump_linux_cpu_info(ui, dump),
-                        LinuxEnviron => self.update_raw_dump_linux_environ(ui, dump),
-                        LinuxLsbRelease => self.update_raw_dump_linux_lsb_release(ui, dump),
-                        LinuxProcStatus => self.update_raw_dump_linux_proc_status(ui, dump),
+                        ExceptionStream => self.update_raw_dump_exception(ui, dump, &text),
+                        MiscInfoStream => self.update_raw_dump_misc_info(ui, dump, &text),
+                        CrashpadInfoStream => self.update_raw_dump_crashpad_info(ui, dump, &text),
                         MozMacosCrashInfoStream => {
-                            self.update_raw_dump_moz_macos_crash_info(ui, dump)
+                            self.update_raw_dump_moz_macos_crash_info(ui, dump, &text)
                         }
-                        _ => {}
+                        ModuleListStream => self.update_raw_dump_module_list(ui, dump, &text),
+                        ThreadListStream => self.update_raw_dump_thread_list(ui, dump, &text),
+                        ThreadExListStream => self.update_raw_dump_thread_ex_list(ui, dump, &text),
+                        MemoryListStream => self.update_raw_dump_memory_list(ui, dump, &text),
+                        Memory64ListStream => self.update_raw_dump_memory_64_list(ui, dump, &text),
+                        MemoryInfoListStream => {
+                            self.update_raw_dump_memory_info_list(ui, dump, &text)
+                        }
+                        LinuxCpuInfo => self.update_raw_dump_linux_cpu_info(ui, dump, &text),
+                        LinuxProcStatus => self.update_raw_dump_linux_proc_status(ui, dump, &text),
+                        LinuxMaps => self.update_raw_dump_linux_maps(ui, dump, &text),
+                        LinuxEnviron => self.update_raw_dump_linux_environ(ui, dump, &text),
+                        LinuxCmdLine => self.update_raw_dump_linux_cmd_line(ui, dump, &text),
+                        LinuxLsbRelease => self.update_raw_dump_linux_lsb_release(ui, dump, &text),
+                        _ => raw_text_view(ui, &text),
                     }
                 }
             });
@@ -78,49 +180,59 @@ impl MyApp {
     fn ui_raw_dump_streams(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
         ui.heading("Streams");
         ui.separator();
-        ui.selectable_value(&mut self.raw_dump_ui_state.cur_stream, 0, "<summary>");
+        ui.selectable_value(&mut self.doc.raw_dump_ui_state.cur_stream, 0, "<summary>");
+        ui.selectable_value(
+            &mut self.doc.raw_dump_ui_state.cur_stream,
+            FILE_BYTES_VIEW,
+            "<file bytes>",
+        );
 
         for (i, stream) in dump.all_streams().enumerate() {
-            use MINIDUMP_STREAM_TYPE::*;
-            let (supported, label) =
-                if let Some(stream_type) = MINIDUMP_STREAM_TYPE::from_u32(stream.stream_type) {
-                    let supported = matches!(
-                        stream_type,
-                        SystemInfoStream
-                            | MiscInfoStream
-                            | ThreadNamesStream
-                            | ThreadListStream
-                            | AssertionInfoStream
-                            | BreakpadInfoStream
-                            | CrashpadInfoStream
-                            | ExceptionStream
-                            | ModuleListStream
-                            | UnloadedModuleListStream
-                            | MemoryListStream
-                            | Memory64ListStream
-                            | MemoryInfoListStream
-                            | MozMacosCrashInfoStream
-                            | LinuxCmdLine
-                            | LinuxMaps
-                            | LinuxCpuInfo
-                            | LinuxEnviron
-                            | LinuxLsbRelease
-                            | LinuxProcStatus
-                    );
-
-                    (supported, format!("{stream_type:?}"))
-                } else {
-                    (false, "<unknown>".to_string())
-                };
-
-            ui.add_enabled_ui(supported, |ui| {
-                ui.selectable_value(&mut self.raw_dump_ui_state.cur_stream, i + 1, label);
+            // Even if we can't decode a stream, we can still show its bytes
+            let selectable = dump.get_raw_stream(stream.stream_type).is_ok();
+            let label = stream_label(stream.stream_type);
+            ui.add_enabled_ui(selectable, |ui| {
+                if ui
+                    .selectable_value(&mut self.doc.raw_dump_ui_state.cur_stream, i + 1, label)
+                    .changed()
+                {
+                    self.doc.raw_dump_ui_state.hex_offset = 0;
+                }
             });
         }
     }
 
     fn ui_raw_dump_top_level(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        ui.heading("Minidump Streams");
+        if let Some(producer) = dump_producer(dump) {
+            ui.label(format!("produced by: {producer}"));
+            ui.add_space(10.0);
+        }
+        ui.horizontal(|ui| {
+            ui.heading("Minidump Streams");
+            if ui.button("📋 copy as CSV").clicked() {
+                ui.output().copied_text = stream_directory_csv(dump);
+            }
+            if ui.button("💾 save as CSV...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("csv", &["csv"])
+                    .save_file()
+                {
+                    if let Err(e) = std::fs::write(&path, stream_directory_csv(dump)) {
+                        tracing::error!("failed to write {}: {e}", path.display());
+                    }
+                }
+            }
+            if ui.button("💾 save as JSON...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("json", &["json"])
+                    .save_file()
+                {
+                    if let Err(e) = std::fs::write(&path, stream_directory_json(dump)) {
+                        tracing::error!("failed to write {}: {e}", path.display());
+                    }
+                }
+            }
+        });
         ui.add_space(20.0);
 
         let row_height = 18.0;
@@ -130,6 +242,7 @@ impl MyApp {
             .column(Size::initial(40.0).at_least(40.0))
             .column(Size::initial(80.0).at_least(40.0))
             .column(Size::initial(80.0).at_least(40.0))
+            .column(Size::initial(200.0).at_least(40.0))
             .column(Size::remainder().at_least(60.0))
             .resizable(true)
             .header(20.0, |mut header| {
@@ -142,6 +255,9 @@ impl MyApp {
                 header.col(|ui| {
                     ui.heading("Vendor");
                 });
+                header.col(|ui| {
+                    ui.heading("Location");
+                });
                 header.col(|ui| {
                     ui.heading("Name");
                 });
@@ -165,41 +281,19 @@ impl MyApp {
                             });
                         });
                         row.col(|ui| {
-                            use MINIDUMP_STREAM_TYPE::*;
-                            let (supported, label) = if let Some(stream_type) =
-                                MINIDUMP_STREAM_TYPE::from_u32(stream.stream_type)
-                            {
-                                let supported = matches!(
-                                    stream_type,
-                                    SystemInfoStream
-                                        | MiscInfoStream
-                                        | ThreadNamesStream
-                                        | ThreadListStream
-                                        | AssertionInfoStream
-                                        | BreakpadInfoStream
-                                        | CrashpadInfoStream
-                                        | ExceptionStream
-                                        | ModuleListStream
-                                        | UnloadedModuleListStream
-                                        | MemoryListStream
-                                        | Memory64ListStream
-                                        | MemoryInfoListStream
-                                        | MozMacosCrashInfoStream
-                                        | LinuxCmdLine
-                                        | LinuxMaps
-                                        | LinuxCpuInfo
-                                        | LinuxEnviron
-                                        | LinuxLsbRelease
-                                        | LinuxProcStatus
-                                );
-                                (supported, format!("{stream_type:?}"))
-                            } else {
-                                (false, "<unknown>".to_string())
-                            };
-
-                            if supported {
+                            let location = &stream.location;
+                            if ui.link(self.format_addr(location.rva as u64)).clicked() {
+                                self.doc.raw_dump_ui_state.cur_stream = FILE_BYTES_VIEW;
+                                self.doc.raw_dump_ui_state.hex_offset = location.rva as usize;
+                            }
+                            ui.label(format!("+ 0x{:x}", location.data_size));
+                        });
+                        row.col(|ui| {
+                            let label = stream_label(stream.stream_type);
+                            if dump.get_raw_stream(stream.stream_type).is_ok() {
                                 if ui.link(label).clicked() {
-                                    self.raw_dump_ui_state.cur_stream = i + 1;
+                                    self.doc.raw_dump_ui_state.cur_stream = i + 1;
+                                    self.doc.raw_dump_ui_state.hex_offset = 0;
                                 }
                             } else {
                                 ui.label(label);
@@ -209,6 +303,12 @@ impl MyApp {
                 }
             });
 
+        ui.add_space(20.0);
+        ui.separator();
+        ui.heading("Minidump Layout");
+        ui.add_space(10.0);
+        self.ui_raw_dump_layout(ui, dump);
+
         ui.add_space(20.0);
         ui.separator();
         ui.heading("Minidump Metadata");
@@ -223,375 +323,1599 @@ impl MyApp {
         );
     }
 
-    fn update_raw_dump_misc_info(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_stream::<minidump::MinidumpMiscInfo>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+    /// The current document's dump as the minidump parser sees it, so decompressed
+    /// if the file is compressed
+    fn dump_file_bytes(&mut self) -> std::io::Result<Arc<Mmap>> {
+        if let Some(bytes) = &self.doc.raw_dump_ui_state.file_bytes {
+            return Ok(bytes.clone());
         }
-        let stream = stream.unwrap();
-        let mut bytes = Vec::new();
-        stream.print(&mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.add(
-            egui::TextEdit::multiline(&mut &*text)
-                .font(TextStyle::Monospace)
-                .desired_width(f32::INFINITY),
-        );
+        let Some(path) = &self.doc.path else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no file opened",
+            ));
+        };
+        let bytes = match crate::processor::decompress_dump(path)? {
+            Some(decompressed) => decompressed,
+            // Safety: same caveats as the mapping the minidump itself is read from
+            None => unsafe { Mmap::map(&std::fs::File::open(path)?)? },
+        };
+        let bytes = Arc::new(bytes);
+        self.doc.raw_dump_ui_state.file_bytes = Some(bytes.clone());
+        Ok(bytes)
     }
 
-    fn update_raw_dump_moz_macos_crash_info(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_stream::<minidump::MinidumpMacCrashInfo>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+    fn ui_raw_dump_file_bytes(&mut self, ui: &mut Ui) {
+        match self.dump_file_bytes() {
+            Ok(bytes) => self.ui_raw_dump_memory_hex(ui, 0, &bytes, None),
+            Err(e) => {
+                ui.label("Failed to read file");
+                ui.label(e.to_string());
+            }
         }
-        let stream = stream.unwrap();
-        let mut bytes = Vec::new();
-        stream.print(&mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.add(
-            egui::TextEdit::multiline(&mut &*text)
-                .font(TextStyle::Monospace)
-                .desired_width(f32::INFINITY),
-        );
     }
 
-    fn update_raw_dump_thread_names(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_stream::<minidump::MinidumpThreadNames>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+    fn ui_raw_dump_layout(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
+        const HEADER_SIZE: u64 = 32;
+        const DIRECTORY_ENTRY_SIZE: u64 = 12;
+        const COLORS: &[Color32] = &[
+            Color32::from_rgb(70, 130, 180),
+            Color32::from_rgb(60, 160, 90),
+            Color32::from_rgb(200, 140, 40),
+            Color32::from_rgb(150, 90, 180),
+            Color32::from_rgb(190, 80, 80),
+            Color32::from_rgb(60, 170, 170),
+        ];
+
+        // Every range of the file that we know the purpose of
+        let mut regions = vec![
+            ("header".to_owned(), 0, HEADER_SIZE),
+            (
+                "stream directory".to_owned(),
+                dump.header.stream_directory_rva as u64,
+                dump.header.stream_count as u64 * DIRECTORY_ENTRY_SIZE,
+            ),
+        ];
+        for stream in dump.all_streams() {
+            let name = MINIDUMP_STREAM_TYPE::from_u32(stream.stream_type)
+                .map(|stream_type| format!("{stream_type:?}"))
+                .unwrap_or_else(|| format!("0x{:08x}", stream.stream_type));
+            regions.push((
+                name,
+                stream.location.rva as u64,
+                stream.location.data_size as u64,
+            ));
         }
-        let stream = stream.unwrap();
-        let mut bytes = Vec::new();
-        stream.print(&mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.add(
-            egui::TextEdit::multiline(&mut &*text)
-                .font(TextStyle::Monospace)
-                .desired_width(f32::INFINITY),
-        );
-    }
+        let regions_end = regions
+            .iter()
+            .map(|(_, start, len)| start + len)
+            .max()
+            .unwrap_or(0);
+        let file_len = self
+            .dump_file_bytes()
+            .map(|bytes| bytes.len() as u64)
+            .unwrap_or(0)
+            .max(regions_end)
+            .max(1);
 
-    fn update_raw_dump_system_info(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_stream::<minidump::MinidumpSystemInfo>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+        // Draw the map, with unclaimed bytes left dark
+        let (rect, response) =
+            ui.allocate_exact_size(Vec2::new(ui.available_width(), 30.0), Sense::hover());
+        let to_x = |offset: u64| rect.left() + rect.width() * (offset as f32 / file_len as f32);
+        let painter = ui.painter();
+        painter.rect_filled(rect, 0.0, Color32::from_gray(40));
+        for (idx, (_name, start, len)) in regions.iter().enumerate() {
+            let min_x = to_x(*start);
+            let max_x = to_x(start + len).max(min_x + 1.0);
+            painter.rect_filled(
+                Rect::from_min_max(pos2(min_x, rect.top()), pos2(max_x, rect.bottom())),
+                0.0,
+                COLORS[idx % COLORS.len()],
+            );
         }
-        let stream = stream.unwrap();
-        let mut bytes = Vec::new();
-        stream.print(&mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.add(
-            egui::TextEdit::multiline(&mut &*text)
-                .font(TextStyle::Monospace)
-                .desired_width(f32::INFINITY),
-        );
-    }
+        if let Some(pos) = response.hover_pos() {
+            let offset = ((pos.x - rect.left()) / rect.width() * file_len as f32) as u64;
+            let hovered = regions
+                .iter()
+                .filter(|(_, start, len)| (*start..start + len).contains(&offset))
+                .map(|(name, start, len)| format!("{name}: 0x{start:08x}..0x{:08x}", start + len))
+                .collect::<Vec<_>>();
+            let text = if hovered.is_empty() {
+                format!("0x{offset:08x}: not in any stream")
+            } else {
+                hovered.join("\n")
+            };
+            response.on_hover_text(text);
+        }
+        ui.label(format!("{file_len} bytes"));
 
-    fn update_raw_dump_thread_list(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let brief = self.settings.raw_dump_brief;
-        let stream = dump.get_stream::<minidump::MinidumpThreadList>();
-        let memory = dump.get_memory();
-        let system = dump.get_stream::<minidump::MinidumpSystemInfo>();
-        let misc = dump.get_stream::<minidump::MinidumpMiscInfo>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+        // Report the gaps and overlaps
+        regions.sort_by_key(|(_, start, _)| *start);
+        let mut covered_end = 0;
+        for (idx, (name, start, len)) in regions.iter().enumerate() {
+            if *start > covered_end {
+                ui.label(format!(
+                    "gap: 0x{covered_end:08x}..0x{start:08x} ({} bytes)",
+                    start - covered_end
+                ));
+            }
+            for (other_name, other_start, other_len) in &regions[idx + 1..] {
+                if *other_start < start + len && *other_len > 0 && *len > 0 {
+                    ui.colored_label(
+                        Color32::from_rgb(220, 140, 0),
+                        format!(
+                            "overlap: {name} (0x{start:08x}..0x{:08x}) and {other_name} (0x{other_start:08x}..0x{:08x})",
+                            start + len,
+                            other_start + other_len,
+                        ),
+                    );
+                }
+            }
+            covered_end = covered_end.max(start + len);
         }
-        let stream = stream.unwrap();
-        let mut bytes = Vec::new();
-        stream
-            .print(
-                &mut bytes,
-                memory.as_ref(),
-                system.as_ref().ok(),
-                misc.as_ref().ok(),
-                brief,
-            )
-            .unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.add(
-            egui::TextEdit::multiline(&mut &*text)
-                .font(TextStyle::Monospace)
-                .desired_width(f32::INFINITY),
+        if covered_end < file_len {
+            ui.label(format!(
+                "gap: 0x{covered_end:08x}..0x{file_len:08x} ({} bytes)",
+                file_len - covered_end
+            ));
+        }
+        ui.label(
+            "(gaps usually hold data that streams point to, like memory contents and strings)",
         );
     }
 
-    fn update_raw_dump_assertion_info(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_stream::<minidump::MinidumpAssertion>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+    fn update_raw_dump_system_info(
+        &mut self,
+        ui: &mut Ui,
+        _dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        raw_text_view(ui, text);
+    }
+
+    fn update_raw_dump_thread_names(
+        &mut self,
+        ui: &mut Ui,
+        _dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        raw_text_view(ui, text);
+    }
+
+    fn update_raw_dump_assertion_info(
+        &mut self,
+        ui: &mut Ui,
+        _dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        ui.horizontal_wrapped(|ui| raw_text_view(ui, text));
+    }
+
+    fn update_raw_dump_breakpad_info(
+        &mut self,
+        ui: &mut Ui,
+        _dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        ui.horizontal_wrapped(|ui| raw_text_view(ui, text));
+    }
+
+    fn update_raw_dump_unloaded_module_list(
+        &mut self,
+        ui: &mut Ui,
+        _dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        raw_text_view(ui, text);
+    }
+
+    fn update_raw_dump_linux_cmd_line(
+        &mut self,
+        ui: &mut Ui,
+        _dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        raw_monospace(ui, text);
+    }
+
+    fn update_raw_dump_linux_lsb_release(
+        &mut self,
+        ui: &mut Ui,
+        _dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        raw_monospace(ui, text);
+    }
+
+    fn update_raw_dump_exception(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>, text: &StreamText) {
+        let system_info = dump.get_stream::<minidump::MinidumpSystemInfo>();
+        let is_windows = system_info
+            .as_ref()
+            .is_ok_and(|info| info.os == minidump::system_info::Os::Windows);
+        if let Ok(stream) = dump.get_stream::<minidump::MinidumpException>() {
+            let record = &stream.raw.exception_record;
+            let num_params =
+                (record.number_parameters as usize).min(record.exception_information.len());
+            let params = &record.exception_information[..num_params];
+            if is_windows {
+                if let Some(description) =
+                    self.describe_windows_exception(record.exception_code, params)
+                {
+                    ui.heading(description);
+                    ui.add_space(10.0);
+                }
+            }
+
+            // Other OSes don't use these in any standard way, so just show them as is
+            if !params.is_empty() {
+                let labels = if is_windows {
+                    windows_exception_params(record.exception_code)
+                } else {
+                    &[]
+                };
+                ui.heading("Exception Parameters");
+                let ctx = ui.ctx().clone();
+                crate::listing(
+                    ui,
+                    &ctx,
+                    0,
+                    params
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, &param)| match labels.get(idx) {
+                            Some(&(label, kind)) => {
+                                (label.to_owned(), self.describe_exception_param(kind, param))
+                            }
+                            None => (format!("parameter {idx}"), format!("0x{param:x}")),
+                        }),
+                );
+                ui.add_space(10.0);
+                ui.separator();
+            }
         }
-        let stream = stream.unwrap();
-        ui.horizontal_wrapped(|ui| {
-            let mut bytes = Vec::new();
-            stream.print(&mut bytes).unwrap();
-            let text = String::from_utf8(bytes).unwrap();
-            ui.add(
-                egui::TextEdit::multiline(&mut &*text)
-                    .font(TextStyle::Monospace)
-                    .desired_width(f32::INFINITY),
-            );
-        });
+
+        raw_text_view(ui, text);
     }
 
-    fn update_raw_dump_crashpad_info(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_stream::<minidump::MinidumpCrashpadInfo>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+    fn describe_exception_param(&self, kind: ExceptionParam, param: u64) -> String {
+        match kind {
+            ExceptionParam::AccessType => match param {
+                0 => "read".to_owned(),
+                1 => "write".to_owned(),
+                8 => "execute (DEP violation)".to_owned(),
+                _ => format!("unknown ({param})"),
+            },
+            ExceptionParam::Address => self.format_addr(param),
+            ExceptionParam::Code32 => format!("0x{param:08x}"),
+            ExceptionParam::FastFailCode => {
+                // The most common ones, from winnt.h
+                let name = match param {
+                    2 => "FAST_FAIL_STACK_COOKIE_CHECK_FAILURE",
+                    3 => "FAST_FAIL_CORRUPT_LIST_ENTRY",
+                    4 => "FAST_FAIL_INCORRECT_STACK",
+                    5 => "FAST_FAIL_INVALID_ARG",
+                    6 => "FAST_FAIL_GS_COOKIE_INIT",
+                    7 => "FAST_FAIL_FATAL_APP_EXIT",
+                    10 => "FAST_FAIL_GUARD_ICALL_CHECK_FAILURE",
+                    13 => "FAST_FAIL_INVALID_BUFFER_ACCESS",
+                    14 => "FAST_FAIL_INVALID_BALANCED_TREE",
+                    18 => "FAST_FAIL_INVALID_REFERENCE_COUNT",
+                    23 => "FAST_FAIL_INVALID_FIBER_SWITCH",
+                    38 => "FAST_FAIL_GUARD_SS_FAILURE",
+                    _ => return param.to_string(),
+                };
+                format!("{name} ({param})")
+            }
         }
-        let stream = stream.unwrap();
-        ui.horizontal_wrapped(|ui| {
-            let mut bytes = Vec::new();
-            stream.print(&mut bytes).unwrap();
-            let text = String::from_utf8(bytes).unwrap();
-            ui.add(
-                egui::TextEdit::multiline(&mut &*text)
-                    .font(TextStyle::Monospace)
-                    .desired_width(f32::INFINITY),
-            );
-        });
     }
 
-    fn update_raw_dump_breakpad_info(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_stream::<minidump::MinidumpBreakpadInfo>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+    /// Turns the exception_information of the common Windows exceptions into a sentence
+    fn describe_windows_exception(&self, code: u32, params: &[u64]) -> Option<String> {
+        let kind = match code {
+            EXCEPTION_ACCESS_VIOLATION => "Access violation",
+            EXCEPTION_IN_PAGE_ERROR => "In-page error",
+            _ => return None,
+        };
+        let (mut access, mut address, mut status) = (None, None, None);
+        for (&(_, meaning), &param) in windows_exception_params(code).iter().zip(params) {
+            match meaning {
+                ExceptionParam::AccessType => access = Some(param),
+                ExceptionParam::Address => address = Some(param),
+                ExceptionParam::Code32 => status = Some(param),
+                ExceptionParam::FastFailCode => {}
+            }
         }
-        let stream = stream.unwrap();
-        ui.horizontal_wrapped(|ui| {
-            let mut bytes = Vec::new();
-            stream.print(&mut bytes).unwrap();
-            let text = String::from_utf8(bytes).unwrap();
-            ui.add(
-                egui::TextEdit::multiline(&mut &*text)
-                    .font(TextStyle::Monospace)
-                    .desired_width(f32::INFINITY),
-            );
+        let access = match access? {
+            0 => "reading",
+            1 => "writing",
+            8 => "executing",
+            _ => "accessing",
+        };
+        let address = self.format_addr(address?);
+        let mut description = format!("{kind} {access} {address}");
+        if let Some(status) = status {
+            let status = self.describe_exception_param(ExceptionParam::Code32, status);
+            description.push_str(&format!(" (NTSTATUS {status})"));
+        }
+        Some(description)
+    }
+
+    /// The streams whose printing is affected by brief mode get this at the top
+    fn ui_raw_dump_brief_toggle(&mut self, ui: &mut Ui) {
+        ui.checkbox(&mut self.settings.raw_dump_brief, "hide memory dumps");
+        ui.separator();
+    }
+
+    fn update_raw_dump_thread_list(
+        &mut self,
+        ui: &mut Ui,
+        _dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        self.ui_raw_dump_brief_toggle(ui);
+        raw_text_view(ui, text);
+    }
+
+    fn update_raw_dump_thread_ex_list(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        let threads = dump
+            .get_raw_stream(MINIDUMP_STREAM_TYPE::ThreadExListStream as u32)
+            .ok()
+            .and_then(parse_thread_ex_list);
+        let Some(threads) = threads else {
+            return raw_text_view(ui, text);
+        };
+
+        let row_height = 18.0;
+        let addr_column = self.addr_column(ui, &TextStyle::Monospace.resolve(ui.style()));
+        TableBuilder::new(ui)
+            .striped(true)
+            .cell_layout(egui::Layout::left_to_right().with_cross_align(egui::Align::Center))
+            .column(Size::initial(80.0).at_least(40.0))
+            .column(Size::initial(60.0).at_least(40.0))
+            .column(Size::initial(60.0).at_least(40.0))
+            .column(addr_column)
+            .column(addr_column)
+            .column(Size::initial(80.0).at_least(40.0))
+            .column(addr_column)
+            .column(Size::remainder().at_least(60.0))
+            .resizable(true)
+            .header(20.0, |mut header| {
+                for heading in [
+                    "Thread Id",
+                    "Suspend",
+                    "Priority",
+                    "TEB",
+                    "Stack",
+                    "Stack Size",
+                    "Backing Store",
+                    "Backing Store Size",
+                ] {
+                    header.col(|ui| {
+                        ui.heading(heading);
+                    });
+                }
+            })
+            .body(|mut body| {
+                for thread in &threads {
+                    let cells = [
+                        format!("{0} ({0:#x})", thread.thread_id),
+                        thread.suspend_count.to_string(),
+                        format!("{}/{}", thread.priority_class, thread.priority),
+                        self.format_addr(thread.teb),
+                        self.format_addr(thread.stack_start),
+                        format!("{:#x}", thread.stack_size),
+                        self.format_addr(thread.backing_store_start),
+                        format!("{:#x}", thread.backing_store_size),
+                    ];
+                    body.row(row_height, |mut row| {
+                        for cell in cells {
+                            row.col(|ui| {
+                                ui.monospace(cell);
+                            });
+                        }
+                    });
+                }
+            });
+    }
+
+    fn update_raw_dump_memory_64_list(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        let stream = match dump.get_stream::<minidump::MinidumpMemory64List>() {
+            Ok(stream) => stream,
+            Err(_) => return raw_text_view(ui, text),
+        };
+        let regions = stream
+            .iter()
+            .map(|region| (region.base_address, region.size, region.bytes))
+            .collect::<Vec<_>>();
+        self.ui_raw_dump_memory_regions(ui, &regions, text);
+    }
+
+    fn update_raw_dump_memory_list(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        self.ui_raw_dump_brief_toggle(ui);
+        let stream = match dump.get_stream::<minidump::MinidumpMemoryList>() {
+            Ok(stream) => stream,
+            Err(_) => return raw_text_view(ui, text),
+        };
+        let regions = stream
+            .iter()
+            .map(|region| (region.base_address, region.size, region.bytes))
+            .collect::<Vec<_>>();
+        self.ui_raw_dump_memory_regions(ui, &regions, text);
+    }
+
+    /// A list of (base, size, bytes) memory regions, with the selected one shown in
+    /// the hex viewer and the stream's text otherwise
+    fn ui_raw_dump_memory_regions(
+        &mut self,
+        ui: &mut Ui,
+        regions: &[(u64, u64, &[u8])],
+        text: &StreamText,
+    ) {
+        let crash_address = self
+            .doc
+            .processed
+            .as_ref()
+            .and_then(|state| state.as_ref().ok())
+            .and_then(|state| state.exception_info.as_ref())
+            .map(|info| info.address.0);
+
+        ui.horizontal_top(|ui| {
+            ui.vertical(|ui| {
+                ui.selectable_value(
+                    &mut self.doc.raw_dump_ui_state.cur_memory_region,
+                    None,
+                    "<listing>",
+                );
+                for (idx, &(base_address, size, _)) in regions.iter().enumerate() {
+                    let mut label = format!("{} ({size} bytes)", self.format_addr(base_address));
+                    if crash_address.is_some_and(|addr| region_contains(base_address, size, addr)) {
+                        label.push_str(" ⚠ crash");
+                    }
+                    if ui
+                        .selectable_value(
+                            &mut self.doc.raw_dump_ui_state.cur_memory_region,
+                            Some(idx),
+                            label,
+                        )
+                        .changed()
+                    {
+                        self.doc.raw_dump_ui_state.hex_offset = 0;
+                    }
+                }
+            });
+            ui.separator();
+            ui.vertical(|ui| {
+                let region = self
+                    .doc
+                    .raw_dump_ui_state
+                    .cur_memory_region
+                    .and_then(|idx| regions.get(idx));
+                if let Some(&(base_address, _, bytes)) = region {
+                    self.ui_raw_dump_memory_hex(ui, base_address, bytes, crash_address);
+                } else {
+                    raw_text_view(ui, text);
+                }
+            });
         });
     }
 
-    fn update_raw_dump_exception(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let system_info = dump.get_stream::<minidump::MinidumpSystemInfo>();
-        let misc_info = dump.get_stream::<minidump::MinidumpMiscInfo>();
-        let stream = dump.get_stream::<minidump::MinidumpException>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+    fn update_raw_dump_memory_info_list(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        use minidump::format::{MemoryProtection, MemoryState, MemoryType};
+        let stream = match dump.get_stream::<minidump::MinidumpMemoryInfoList>() {
+            Ok(stream) => stream,
+            Err(_) => return raw_text_view(ui, text),
+        };
+        let modules = dump.get_stream::<minidump::MinidumpModuleList>().ok();
+        let threads = dump.get_stream::<minidump::MinidumpThreadList>().ok();
+        let crash_address = self
+            .doc
+            .processed
+            .as_ref()
+            .and_then(|state| state.as_ref().ok())
+            .and_then(|state| state.exception_info.as_ref())
+            .map(|info| info.address.0);
+
+        let mut regions = stream.iter().collect::<Vec<_>>();
+        match self.doc.raw_dump_ui_state.memory_info_sort {
+            RegionSort::File => {}
+            RegionSort::Address => regions.sort_by_key(|info| info.raw.base_address),
+            RegionSort::Size => regions.sort_by_key(|info| info.raw.region_size),
         }
-        let stream = stream.unwrap();
-        ui.horizontal_wrapped(|ui| {
-            let mut bytes = Vec::new();
-            stream
-                .print(
-                    &mut bytes,
-                    system_info.as_ref().ok(),
-                    misc_info.as_ref().ok(),
-                )
-                .unwrap();
-            let text = String::from_utf8(bytes).unwrap();
-            ui.add(
-                egui::TextEdit::multiline(&mut &*text)
-                    .font(TextStyle::Monospace)
-                    .desired_width(f32::INFINITY),
-            );
+        if self.doc.raw_dump_ui_state.memory_info_sort_descending {
+            regions.reverse();
+        }
+
+        let overlaps = |base: u64, size: u64, start: u64, len: u64| {
+            base < start.saturating_add(len) && start < base.saturating_add(size)
+        };
+        // A best guess at what each region is for, from what else is in it
+        let classify = |base: u64, size: u64, state: MemoryState, ty: MemoryType| {
+            let is_stack = threads.as_ref().is_some_and(|threads| {
+                threads.threads.iter().any(|thread| {
+                    let stack = &thread.raw.stack;
+                    overlaps(
+                        base,
+                        size,
+                        stack.start_of_memory_range,
+                        stack.memory.data_size as u64,
+                    )
+                })
+            });
+            let is_image = ty.contains(MemoryType::MEM_IMAGE)
+                || modules.as_ref().is_some_and(|modules| {
+                    modules
+                        .iter()
+                        .any(|module| overlaps(base, size, module.base_address(), module.size()))
+                });
+            if is_stack {
+                "stack"
+            } else if is_image {
+                "image"
+            } else if state.contains(MemoryState::MEM_COMMIT)
+                && ty.contains(MemoryType::MEM_PRIVATE)
+            {
+                "heap?"
+            } else {
+                ""
+            }
+        };
+
+        let warn_color = Color32::from_rgb(200, 60, 60);
+        let text_color = ui.visuals().text_color();
+        let row_height = 18.0;
+        let addr_column = self.addr_column(ui, &TextStyle::Monospace.resolve(ui.style()));
+        TableBuilder::new(ui)
+            .striped(true)
+            .cell_layout(egui::Layout::left_to_right().with_cross_align(egui::Align::Center))
+            .column(addr_column)
+            .column(Size::initial(80.0).at_least(40.0))
+            .column(Size::initial(50.0).at_least(40.0))
+            .column(Size::initial(100.0).at_least(40.0))
+            .column(Size::initial(100.0).at_least(40.0))
+            .column(addr_column)
+            .column(Size::initial(60.0).at_least(40.0))
+            .column(Size::remainder().at_least(60.0))
+            .resizable(true)
+            .header(20.0, |mut header| {
+                let ui_state = &mut self.doc.raw_dump_ui_state;
+                header.col(|ui| {
+                    sort_header(
+                        ui,
+                        &mut ui_state.memory_info_sort,
+                        &mut ui_state.memory_info_sort_descending,
+                        "Base",
+                        RegionSort::Address,
+                    );
+                });
+                header.col(|ui| {
+                    sort_header(
+                        ui,
+                        &mut ui_state.memory_info_sort,
+                        &mut ui_state.memory_info_sort_descending,
+                        "Size",
+                        RegionSort::Size,
+                    );
+                });
+                for heading in ["Prot", "State", "Type", "Allocation Base", "Kind", "Notes"] {
+                    header.col(|ui| {
+                        ui.heading(heading);
+                    });
+                }
+            })
+            .body(|mut body| {
+                for info in &regions {
+                    let base = info.raw.base_address;
+                    let size = info.raw.region_size;
+                    let mut prot = String::new();
+                    prot.push(if info.is_readable() { 'R' } else { '-' });
+                    prot.push(if info.is_writable() { 'W' } else { '-' });
+                    prot.push(if info.is_executable() { 'X' } else { '-' });
+
+                    let mut notes = vec![];
+                    if crash_address.is_some_and(|addr| region_contains(base, size, addr)) {
+                        notes.push("⚠ crash");
+                    }
+                    if info.is_writable() && info.is_executable() {
+                        notes.push("W^X violation");
+                    }
+                    if info.protection.contains(MemoryProtection::PAGE_GUARD) {
+                        notes.push("guard page");
+                    }
+                    let color = if notes.is_empty() {
+                        text_color
+                    } else {
+                        warn_color
+                    };
+                    let cells = [
+                        self.format_addr(base),
+                        format!("0x{size:x}"),
+                        prot,
+                        format!("{:?}", info.state),
+                        format!("{:?}", info.ty),
+                        self.format_addr(info.raw.allocation_base),
+                        classify(base, size, info.state, info.ty).to_owned(),
+                        notes.join(", "),
+                    ];
+                    body.row(row_height, |mut row| {
+                        for cell in cells {
+                            row.col(|ui| {
+                                ui.label(RichText::new(cell).monospace().color(color));
+                            });
+                        }
+                    });
+                }
+            });
+    }
+
+    /// A classic `address | hex bytes | ascii` view of some memory, a page at a time
+    fn ui_raw_dump_memory_hex(
+        &mut self,
+        ui: &mut Ui,
+        base_address: u64,
+        bytes: &[u8],
+        crash_address: Option<u64>,
+    ) {
+        const BYTES_PER_ROW: usize = 16;
+        const BYTES_PER_PAGE: usize = BYTES_PER_ROW * 32;
+
+        // Highlight the pointer-sized value at the crash address
+        let pointer_size = match self.doc.pointer_width {
+            minidump::system_info::PointerWidth::Bits64 => 8,
+            _ => 4,
+        };
+        let highlighted = |addr: u64| {
+            crash_address.is_some_and(|crash| region_contains(crash, pointer_size, addr))
+        };
+
+        let mut offset = self.doc.raw_dump_ui_state.hex_offset.min(bytes.len());
+        ui.horizontal(|ui| {
+            if ui.button("◀").clicked() {
+                offset = offset.saturating_sub(BYTES_PER_PAGE);
+            }
+            ui.label(format!("offset 0x{offset:x} of 0x{:x}", bytes.len()));
+            if ui.button("▶").clicked() && offset + BYTES_PER_PAGE < bytes.len() {
+                offset += BYTES_PER_PAGE;
+            }
+            ui.label("jump to offset:");
+            let response = ui.text_edit_singleline(&mut self.doc.raw_dump_ui_state.hex_jump);
+            if response.lost_focus() && ui.input().key_pressed(egui::Key::Enter) {
+                let input = self.doc.raw_dump_ui_state.hex_jump.trim();
+                let target = if let Some(hex) = input.strip_prefix("0x") {
+                    usize::from_str_radix(hex, 16).ok()
+                } else {
+                    input.parse::<usize>().ok()
+                };
+                if let Some(target) = target.filter(|&target| target < bytes.len()) {
+                    offset = target - target % BYTES_PER_ROW;
+                }
+            }
         });
+        self.doc.raw_dump_ui_state.hex_offset = offset;
+        ui.add_space(10.0);
+
+        let page_end = (offset + BYTES_PER_PAGE).min(bytes.len());
+        for row_start in (offset..page_end).step_by(BYTES_PER_ROW) {
+            let row_end = (row_start + BYTES_PER_ROW).min(bytes.len());
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 0.0;
+                ui.monospace(format!(
+                    "{}  ",
+                    self.format_addr(base_address + row_start as u64)
+                ));
+                for idx in row_start..row_start + BYTES_PER_ROW {
+                    let mut text = match bytes.get(idx) {
+                        Some(byte) => RichText::new(format!("{byte:02x} ")).monospace(),
+                        None => RichText::new("   ").monospace(),
+                    };
+                    if idx < row_end && highlighted(base_address + idx as u64) {
+                        text = text
+                            .background_color(Color32::from_rgb(200, 60, 60))
+                            .color(Color32::WHITE);
+                    }
+                    ui.label(text);
+                }
+                let ascii = bytes[row_start..row_end]
+                    .iter()
+                    .map(|&byte| {
+                        if byte.is_ascii_graphic() || byte == b' ' {
+                            byte as char
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>();
+                ui.monospace(format!(" {ascii}"));
+            });
+        }
     }
 
-    fn update_raw_dump_module_list(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_stream::<minidump::MinidumpModuleList>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+    fn update_raw_dump_misc_info(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>, text: &StreamText) {
+        use minidump::format::MiscInfoFlags;
+        use minidump::RawMiscInfo;
+        const FLAGS: &[(&str, MiscInfoFlags)] = &[
+            (
+                "MINIDUMP_MISC1_PROCESS_ID",
+                MiscInfoFlags::MINIDUMP_MISC1_PROCESS_ID,
+            ),
+            (
+                "MINIDUMP_MISC1_PROCESS_TIMES",
+                MiscInfoFlags::MINIDUMP_MISC1_PROCESS_TIMES,
+            ),
+            (
+                "MINIDUMP_MISC1_PROCESSOR_POWER_INFO",
+                MiscInfoFlags::MINIDUMP_MISC1_PROCESSOR_POWER_INFO,
+            ),
+            (
+                "MINIDUMP_MISC3_PROCESS_INTEGRITY",
+                MiscInfoFlags::MINIDUMP_MISC3_PROCESS_INTEGRITY,
+            ),
+            (
+                "MINIDUMP_MISC3_PROCESS_EXECUTE_FLAGS",
+                MiscInfoFlags::MINIDUMP_MISC3_PROCESS_EXECUTE_FLAGS,
+            ),
+            (
+                "MINIDUMP_MISC3_TIMEZONE",
+                MiscInfoFlags::MINIDUMP_MISC3_TIMEZONE,
+            ),
+            (
+                "MINIDUMP_MISC3_PROTECTED_PROCESS",
+                MiscInfoFlags::MINIDUMP_MISC3_PROTECTED_PROCESS,
+            ),
+            (
+                "MINIDUMP_MISC4_BUILDSTRING",
+                MiscInfoFlags::MINIDUMP_MISC4_BUILDSTRING,
+            ),
+            (
+                "MINIDUMP_MISC5_PROCESS_COOKIE",
+                MiscInfoFlags::MINIDUMP_MISC5_PROCESS_COOKIE,
+            ),
+        ];
+
+        // Every version of the struct starts with the same flags field
+        if let Ok(misc_info) = dump.get_stream::<minidump::MinidumpMiscInfo>() {
+            let flags1 = match &misc_info.raw {
+                RawMiscInfo::MiscInfo(raw) => raw.flags1,
+                RawMiscInfo::MiscInfo2(raw) => raw.flags1,
+                RawMiscInfo::MiscInfo3(raw) => raw.flags1,
+                RawMiscInfo::MiscInfo4(raw) => raw.flags1,
+                RawMiscInfo::MiscInfo5(raw) => raw.flags1,
+            };
+            ui.heading("Valid Fields");
+            ui.label(format!("flags1: 0x{flags1:08x}"));
+            let ctx = ui.ctx().clone();
+            crate::listing(
+                ui,
+                &ctx,
+                0,
+                FLAGS.iter().map(|(name, flag)| {
+                    let set = flags1 & flag.bits() != 0;
+                    let mark = if set { "✓" } else { "✗" };
+                    (name.to_string(), mark.to_owned())
+                }),
+            );
+            ui.add_space(10.0);
+            ui.separator();
         }
-        let stream = stream.unwrap();
+        raw_text_view(ui, text);
+    }
 
-        let mut bytes = Vec::new();
-        stream.print(&mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.add(
-            egui::TextEdit::multiline(&mut &*text)
-                .font(TextStyle::Monospace)
-                .desired_width(f32::INFINITY),
+    fn update_raw_dump_crashpad_info(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        use minidump::MinidumpAnnotation;
+        ui.checkbox(
+            &mut self.doc.raw_dump_ui_state.show_raw_crashpad_info,
+            "show raw",
         );
-    }
+        let info = match dump.get_stream::<minidump::MinidumpCrashpadInfo>() {
+            Ok(info) if !self.doc.raw_dump_ui_state.show_raw_crashpad_info => info,
+            _ => return raw_text_view(ui, text),
+        };
+        let modules = dump.get_stream::<minidump::MinidumpModuleList>().ok();
 
-    fn update_raw_dump_unloaded_module_list(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_stream::<minidump::MinidumpUnloadedModuleList>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+        let ctx = ui.ctx().clone();
+        ui.heading("Process");
+        if info.simple_annotations.is_empty() {
+            ui.label("no annotations");
         }
-        let stream = stream.unwrap();
+        crate::listing(
+            ui,
+            &ctx,
+            0,
+            info.simple_annotations
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
 
-        let mut bytes = Vec::new();
-        stream.print(&mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.add(
-            egui::TextEdit::multiline(&mut &*text)
-                .font(TextStyle::Monospace)
-                .desired_width(f32::INFINITY),
+        for (idx, module) in info.module_list.iter().enumerate() {
+            let name = modules
+                .as_ref()
+                .and_then(|modules| modules.iter().nth(module.module_index))
+                .map(|module| basename(&module.code_file()).to_owned())
+                .unwrap_or_else(|| format!("module[{}]", module.module_index));
+            let list_annotations = module
+                .list_annotations
+                .iter()
+                .enumerate()
+                .map(|(idx, value)| (format!("[{idx}]"), value.clone()));
+            let annotation_objects = module.annotation_objects.iter().map(|(key, value)| {
+                let value = match value {
+                    MinidumpAnnotation::String(value) => value.clone(),
+                    other => format!("{other:?}"),
+                };
+                (key.clone(), value)
+            });
+            let annotations = module
+                .simple_annotations
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .chain(annotation_objects)
+                .chain(list_annotations)
+                .collect::<Vec<_>>();
+            if annotations.is_empty() {
+                continue;
+            }
+            ui.add_space(10.0);
+            ui.heading(name);
+            crate::listing(ui, &ctx, idx as u64 + 1, annotations);
+        }
+    }
+
+    fn update_raw_dump_moz_macos_crash_info(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        ui.checkbox(
+            &mut self.doc.raw_dump_ui_state.show_raw_mac_crash_info,
+            "show raw",
         );
+        let info = match dump.get_stream::<minidump::MinidumpMacCrashInfo>() {
+            Ok(info) if !self.doc.raw_dump_ui_state.show_raw_mac_crash_info => info,
+            _ => return raw_text_view(ui, text),
+        };
+
+        let ctx = ui.ctx().clone();
+        for (idx, record) in info.raw.iter().enumerate() {
+            let strings = [
+                ("message", record.message()),
+                ("signature string", record.signature_string()),
+                ("backtrace", record.backtrace()),
+                ("message2", record.message2()),
+            ];
+            let annotations = strings
+                .iter()
+                .filter(|(_, value)| value.is_some_and(|value| !value.is_empty()))
+                .count();
+            let module = record.module_path().unwrap_or("<unknown module>");
+            egui::CollapsingHeader::new(format!(
+                "Record {idx}: {} ({annotations} annotations)",
+                basename(module)
+            ))
+            .id_source(("mac crash info", idx))
+            .default_open(info.raw.len() == 1)
+            .show(ui, |ui| {
+                // These aren't all the same integer type, so format them up front
+                let numbers = [
+                    ("thread", record.thread().map(|v| format!("{v:#x}"))),
+                    (
+                        "dialog mode",
+                        record.dialog_mode().map(|v| format!("{v:#x}")),
+                    ),
+                    (
+                        "abort cause",
+                        record.abort_cause().map(|v| format!("{v:#x}")),
+                    ),
+                ];
+                let fields =
+                    std::iter::once(("module path".to_owned(), module.to_owned()))
+                        .chain(strings.iter().filter_map(|(name, value)| {
+                            Some((name.to_string(), value?.to_owned()))
+                        }))
+                        .chain(
+                            numbers
+                                .into_iter()
+                                .filter_map(|(name, value)| Some((name.to_owned(), value?))),
+                        );
+                crate::listing(ui, &ctx, idx as u64, fields);
+            });
+        }
     }
 
-    fn update_raw_dump_memory_list(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let brief = self.settings.raw_dump_brief;
-        let stream = dump.get_stream::<minidump::MinidumpMemoryList>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+    fn update_raw_dump_module_list(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        let modules = match dump.get_stream::<minidump::MinidumpModuleList>() {
+            Ok(modules) => modules,
+            Err(_) => return raw_text_view(ui, text),
+        };
+        let scroll = std::mem::take(&mut self.doc.raw_dump_ui_state.scroll_to_module);
+        let highlight = self.doc.raw_dump_ui_state.highlight_module.as_deref();
+        let state = self
+            .doc
+            .processed
+            .as_ref()
+            .and_then(|state| state.as_ref().ok());
+
+        // The debug id is what symbol servers are keyed on, so make it easy to grab
+        ui.heading("Summary");
+        egui::Grid::new("module_summary")
+            .striped(true)
+            .num_columns(7)
+            .show(ui, |ui| {
+                for heading in ["Module", "Base", "Size", "Version", "Debug Id", "", ""] {
+                    ui.strong(heading);
+                }
+                ui.end_row();
+                for module in modules.iter() {
+                    let debug_id = module
+                        .debug_identifier()
+                        .map(|id| id.breakpad().to_string())
+                        .unwrap_or_default();
+                    ui.monospace(basename(&module.code_file()))
+                        .on_hover_text(module.code_file());
+                    ui.monospace(self.format_addr(module.base_address()));
+                    ui.monospace(format!("{:#x}", module.size()));
+                    ui.monospace(module.version().unwrap_or_default());
+                    ui.monospace(&debug_id);
+                    if ui
+                        .add_enabled(!debug_id.is_empty(), egui::Button::new("📋"))
+                        .on_hover_text("copy debug id")
+                        .clicked()
+                    {
+                        ui.output().copied_text = debug_id;
+                    }
+                    let has_symbols =
+                        state.and_then(|state| crate::module_has_symbols(state, module));
+                    if has_symbols == Some(false) {
+                        ui.colored_label(Color32::from_rgb(220, 140, 0), "⚠ no symbols");
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.add_space(10.0);
+        ui.separator();
+
+        // One block per module so that a single one can be highlighted
+        for (idx, module) in modules.iter().enumerate() {
+            let mut bytes = Vec::new();
+            if module.print(&mut bytes).is_err() {
+                continue;
+            }
+            let mut block = RichText::new(format!(
+                "module[{idx}]\n{}",
+                String::from_utf8_lossy(&bytes)
+            ))
+            .monospace();
+            let highlighted = highlight.is_some_and(|name| basename(&module.code_file()) == name);
+            if highlighted {
+                block = block.background_color(if ui.visuals().dark_mode {
+                    Color32::from_rgb(90, 75, 20)
+                } else {
+                    Color32::from_rgb(255, 240, 180)
+                });
+            }
+            let response = ui.label(block);
+            if highlighted && scroll {
+                response.scroll_to_me(Some(egui::Align::TOP));
+            }
         }
-        let stream = stream.unwrap();
+    }
 
-        let mut bytes = Vec::new();
-        stream.print(&mut bytes, brief).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.add(
-            egui::TextEdit::multiline(&mut &*text)
-                .font(TextStyle::Monospace)
-                .desired_width(f32::INFINITY),
+    fn update_raw_dump_linux_cpu_info(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        ui.checkbox(
+            &mut self.doc.raw_dump_ui_state.show_raw_linux_streams,
+            "show raw",
         );
-    }
-    fn update_raw_dump_memory_64_list(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let brief = self.settings.raw_dump_brief;
-        let stream = dump.get_stream::<minidump::MinidumpMemory64List>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+        let stream = dump.get_raw_stream(MINIDUMP_STREAM_TYPE::LinuxCpuInfo as u32);
+        let stream = match stream {
+            Ok(stream) if !self.doc.raw_dump_ui_state.show_raw_linux_streams => stream,
+            _ => return raw_monospace(ui, text),
+        };
+
+        // Instruction set extensions that often explain why code behaved differently
+        const INTERESTING_FLAGS: &[&str] = &[
+            "sse2", "sse3", "ssse3", "sse4_1", "sse4_2", "popcnt", "avx", "avx2", "avx512f", "fma",
+            "bmi2", "aes", "sha_ni", "neon", "asimd", "sve",
+        ];
+
+        // /proc/cpuinfo is a series of blank-line-separated sections, one per core
+        let ctx = ui.ctx().clone();
+        let text = String::from_utf8_lossy(stream);
+        let cores = text
+            .split("\n\n")
+            .filter(|section| !section.trim().is_empty())
+            .map(parse_linux_key_values)
+            .collect::<Vec<_>>();
+        let first_value = |key: &str| {
+            cores
+                .iter()
+                .flatten()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.clone())
+        };
+        // x86 calls them flags, ARM calls them Features
+        let flags = first_value("flags")
+            .or_else(|| first_value("Features"))
+            .unwrap_or_default();
+        let flags = flags.split_whitespace().collect::<Vec<_>>();
+        let mut summary = vec![
+            (
+                "model name".to_owned(),
+                first_value("model name")
+                    .or_else(|| first_value("CPU part"))
+                    .unwrap_or_default(),
+            ),
+            ("logical cores".to_owned(), cores.len().to_string()),
+        ];
+        if let Some(physical) = first_value("cpu cores") {
+            summary.push(("physical cores".to_owned(), physical));
         }
-        let stream = stream.unwrap();
+        summary.extend(
+            INTERESTING_FLAGS
+                .iter()
+                .filter(|flag| flags.contains(flag))
+                .map(|flag| (flag.to_string(), "✓".to_owned())),
+        );
+        ui.heading("Summary");
+        crate::listing(ui, &ctx, 0, summary);
 
-        let mut bytes = Vec::new();
-        stream.print(&mut bytes, brief).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.add(
-            egui::TextEdit::multiline(&mut &*text)
-                .font(TextStyle::Monospace)
-                .desired_width(f32::INFINITY),
+        for (idx, core) in cores.into_iter().enumerate() {
+            ui.add_space(10.0);
+            egui::CollapsingHeader::new(format!("Core {idx}"))
+                .id_source(("cpuinfo core", idx))
+                .show(ui, |ui| {
+                    crate::listing(ui, &ctx, idx as u64 + 1, core);
+                });
+        }
+    }
+
+    fn update_raw_dump_linux_proc_status(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        ui.checkbox(
+            &mut self.doc.raw_dump_ui_state.show_raw_linux_streams,
+            "show raw",
         );
+        let stream = dump.get_raw_stream(MINIDUMP_STREAM_TYPE::LinuxProcStatus as u32);
+        let stream = match stream {
+            Ok(stream) if !self.doc.raw_dump_ui_state.show_raw_linux_streams => stream,
+            _ => return raw_monospace(ui, text),
+        };
+
+        let threads = dump.get_stream::<minidump::MinidumpThreadList>().ok();
+        let thread_names = dump.get_stream::<minidump::MinidumpThreadNames>().ok();
+        let crashing_tid = dump
+            .get_stream::<minidump::MinidumpException>()
+            .ok()
+            .map(|exception| exception.raw.thread_id);
+        // Point out which of the dump's threads the ids refer to
+        let describe_tid = |tid: u32| {
+            let idx = threads
+                .as_ref()?
+                .threads
+                .iter()
+                .position(|thread| thread.raw.thread_id == tid)?;
+            let mut description = format!("thread #{idx}");
+            if let Some(name) = thread_names.as_ref().and_then(|names| names.get_name(tid)) {
+                description.push_str(&format!(" \"{name}\""));
+            }
+            if crashing_tid == Some(tid) {
+                description.push_str(", the crashing thread");
+            }
+            Some(description)
+        };
+
+        let ctx = ui.ctx().clone();
+        let text = String::from_utf8_lossy(stream);
+        let fields = parse_linux_key_values(&text)
+            .into_iter()
+            .map(|(key, value)| {
+                let pretty = if let Some(size) = format_kb(&value) {
+                    Some(size)
+                } else if matches!(&*key, "Pid" | "Tgid" | "PPid" | "TracerPid") {
+                    value.parse().ok().and_then(&describe_tid)
+                } else {
+                    None
+                };
+                match pretty {
+                    Some(pretty) => (key, format!("{value} ({pretty})")),
+                    None => (key, value),
+                }
+            });
+        crate::listing(ui, &ctx, 0, fields);
     }
 
-    fn update_raw_dump_memory_info_list(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_stream::<minidump::MinidumpMemoryInfoList>();
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
-        }
-        let stream = stream.unwrap();
-        ui.horizontal_wrapped(|ui| {
-            let mut bytes = Vec::new();
-            stream.print(&mut bytes).unwrap();
-            let text = String::from_utf8(bytes).unwrap();
-            ui.add(
-                egui::TextEdit::multiline(&mut &*text)
-                    .font(TextStyle::Monospace)
-                    .desired_width(f32::INFINITY),
+    fn update_raw_dump_linux_environ(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.doc.raw_dump_ui_state.show_raw_linux_streams,
+                "show raw",
             );
+            ui.checkbox(
+                &mut self.doc.raw_dump_ui_state.redact_environ,
+                "redact secrets",
+            )
+            .on_hover_text(format!(
+                "hide the values of variables whose names contain any of: {}",
+                self.settings.sensitive_env_patterns
+            ));
+            if self.doc.raw_dump_ui_state.redact_environ && ui.button("👁 reveal all").clicked() {
+                self.doc.raw_dump_ui_state.redact_environ = false;
+            }
         });
+        let stream = dump.get_raw_stream(MINIDUMP_STREAM_TYPE::LinuxEnviron as u32);
+        let stream = match stream {
+            Ok(stream) if !self.doc.raw_dump_ui_state.show_raw_linux_streams => stream,
+            _ => return raw_monospace(ui, text),
+        };
+
+        let stream = if self.doc.raw_dump_ui_state.redact_environ {
+            self.redacted_environ(stream)
+        } else {
+            stream.to_vec()
+        };
+        let ctx = ui.ctx().clone();
+        let vars = stream
+            .split(|&b| b == 0)
+            .map(String::from_utf8_lossy)
+            .filter_map(|var| {
+                var.split_once('=')
+                    .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            });
+        crate::listing(ui, &ctx, 0, vars);
     }
 
-    fn update_raw_dump_linux_cpu_info(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_raw_stream(MINIDUMP_STREAM_TYPE::LinuxCpuInfo as u32);
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
-        }
-        let stream = stream.unwrap();
-        let mut bytes = Vec::new();
-        print_raw_stream("LinuxCpuInfo", stream, &mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.monospace(text);
+    /// The uppercased bits of variable names whose values get hidden
+    fn sensitive_env_patterns(&self) -> Vec<String> {
+        self.settings
+            .sensitive_env_patterns
+            .split(',')
+            .map(|pattern| pattern.trim().to_uppercase())
+            .filter(|pattern| !pattern.is_empty())
+            .collect()
     }
 
-    fn update_raw_dump_linux_proc_status(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_raw_stream(MINIDUMP_STREAM_TYPE::LinuxProcStatus as u32);
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
-        }
-        let stream = stream.unwrap();
-        let mut bytes = Vec::new();
-        print_raw_stream("LinuxProcStatus", stream, &mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.monospace(text);
+    /// The NUL-separated `NAME=value` environment with sensitive-looking values masked
+    fn redacted_environ(&self, environ: &[u8]) -> Vec<u8> {
+        redact_environ(environ, &self.sensitive_env_patterns())
     }
 
-    fn update_raw_dump_linux_maps(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
+    fn update_raw_dump_linux_maps(
+        &mut self,
+        ui: &mut Ui,
+        dump: &Minidump<Mmap>,
+        text: &StreamText,
+    ) {
+        ui.checkbox(
+            &mut self.doc.raw_dump_ui_state.show_raw_linux_streams,
+            "show raw",
+        );
         let stream = dump.get_raw_stream(MINIDUMP_STREAM_TYPE::LinuxMaps as u32);
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+        let stream = match stream {
+            Ok(stream) if !self.doc.raw_dump_ui_state.show_raw_linux_streams => stream,
+            _ => return raw_monospace(ui, text),
+        };
+
+        let text = String::from_utf8_lossy(stream);
+        let mut entries = text
+            .lines()
+            .filter_map(parse_linux_maps_line)
+            .collect::<Vec<_>>();
+        match self.doc.raw_dump_ui_state.maps_sort {
+            RegionSort::File => {}
+            RegionSort::Address => entries.sort_by_key(|entry| entry.start),
+            RegionSort::Size => entries.sort_by_key(|entry| entry.end - entry.start),
         }
-        let stream = stream.unwrap();
-        let mut bytes = Vec::new();
-        print_raw_stream("LinuxMaps", stream, &mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.monospace(text);
+        if self.doc.raw_dump_ui_state.maps_sort_descending {
+            entries.reverse();
+        }
+
+        let sort_header = |ui: &mut Ui, ui_state: &mut RawDumpUiState, name, sort| {
+            sort_header(
+                ui,
+                &mut ui_state.maps_sort,
+                &mut ui_state.maps_sort_descending,
+                name,
+                sort,
+            );
+        };
+
+        // Executable mappings are the ones that matter for unwinding
+        let exec_color = Color32::from_rgb(200, 60, 60);
+        let text_color = ui.visuals().text_color();
+        let row_height = 18.0;
+        let addr_column = self.addr_column(ui, &TextStyle::Monospace.resolve(ui.style()));
+        TableBuilder::new(ui)
+            .striped(true)
+            .cell_layout(egui::Layout::left_to_right().with_cross_align(egui::Align::Center))
+            .column(addr_column)
+            .column(addr_column)
+            .column(Size::initial(80.0).at_least(40.0))
+            .column(Size::initial(50.0).at_least(40.0))
+            .column(Size::initial(80.0).at_least(40.0))
+            .column(Size::initial(60.0).at_least(40.0))
+            .column(Size::initial(80.0).at_least(40.0))
+            .column(Size::remainder().at_least(60.0))
+            .resizable(true)
+            .header(20.0, |mut header| {
+                header.col(|ui| {
+                    sort_header(
+                        ui,
+                        &mut self.doc.raw_dump_ui_state,
+                        "Start",
+                        RegionSort::Address,
+                    );
+                });
+                header.col(|ui| {
+                    ui.heading("End");
+                });
+                header.col(|ui| {
+                    sort_header(
+                        ui,
+                        &mut self.doc.raw_dump_ui_state,
+                        "Size",
+                        RegionSort::Size,
+                    );
+                });
+                header.col(|ui| {
+                    ui.heading("Perms");
+                });
+                header.col(|ui| {
+                    ui.heading("Offset");
+                });
+                header.col(|ui| {
+                    ui.heading("Device");
+                });
+                header.col(|ui| {
+                    ui.heading("Inode");
+                });
+                header.col(|ui| {
+                    ui.heading("Path");
+                });
+            })
+            .body(|mut body| {
+                for entry in &entries {
+                    let color = if entry.perms.contains('x') {
+                        exec_color
+                    } else {
+                        text_color
+                    };
+                    let cells = [
+                        self.format_addr(entry.start),
+                        self.format_addr(entry.end),
+                        format!("0x{:x}", entry.end - entry.start),
+                        entry.perms.to_owned(),
+                        entry.offset.to_owned(),
+                        entry.device.to_owned(),
+                        entry.inode.to_owned(),
+                        entry.path.to_owned(),
+                    ];
+                    body.row(row_height, |mut row| {
+                        for cell in cells {
+                            row.col(|ui| {
+                                ui.label(RichText::new(cell).monospace().color(color));
+                            });
+                        }
+                    });
+                }
+            });
     }
+}
 
-    fn update_raw_dump_linux_cmd_line(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_raw_stream(MINIDUMP_STREAM_TYPE::LinuxCmdLine as u32);
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+/// A clickable column heading that sorts by `sort`, or flips the order if it already does
+fn sort_header(
+    ui: &mut Ui,
+    cur_sort: &mut RegionSort,
+    descending: &mut bool,
+    name: &str,
+    sort: RegionSort,
+) {
+    let mut label = name.to_owned();
+    if *cur_sort == sort {
+        label.push_str(if *descending { " ⏷" } else { " ⏶" });
+    }
+    if ui.link(RichText::new(label).heading()).clicked() {
+        if *cur_sort == sort {
+            *descending = !*descending;
+        } else {
+            *cur_sort = sort;
+            *descending = false;
         }
-        let stream = stream.unwrap();
-        let mut bytes = Vec::new();
-        print_raw_stream("LinuxCmdLine", stream, &mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.monospace(text);
     }
+}
 
-    fn update_raw_dump_linux_lsb_release(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_raw_stream(MINIDUMP_STREAM_TYPE::LinuxLsbRelease as u32);
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
-        }
-        let stream = stream.unwrap();
-        let mut bytes = Vec::new();
-        print_raw_stream("LinuxLsbRelease", stream, &mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.monospace(text);
+/// What a parameter of an exception record means, to know how to show it
+#[derive(Copy, Clone)]
+enum ExceptionParam {
+    AccessType,
+    Address,
+    Code32,
+    FastFailCode,
+}
+
+const EXCEPTION_ACCESS_VIOLATION: u32 = 0xC0000005;
+const EXCEPTION_IN_PAGE_ERROR: u32 = 0xC0000006;
+const STATUS_STACK_BUFFER_OVERRUN: u32 = 0xC0000409;
+const CPP_EH_EXCEPTION: u32 = 0xE06D7363;
+
+/// The meanings of the exception_information of the common Windows exceptions,
+/// as documented for `EXCEPTION_RECORD`.
+fn windows_exception_params(code: u32) -> &'static [(&'static str, ExceptionParam)] {
+    match code {
+        EXCEPTION_ACCESS_VIOLATION => &[
+            ("access type", ExceptionParam::AccessType),
+            ("address", ExceptionParam::Address),
+        ],
+        EXCEPTION_IN_PAGE_ERROR => &[
+            ("access type", ExceptionParam::AccessType),
+            ("address", ExceptionParam::Address),
+            ("NTSTATUS", ExceptionParam::Code32),
+        ],
+        STATUS_STACK_BUFFER_OVERRUN => &[("fast fail code", ExceptionParam::FastFailCode)],
+        CPP_EH_EXCEPTION => &[
+            ("magic number", ExceptionParam::Code32),
+            ("exception object", ExceptionParam::Address),
+            ("throw info", ExceptionParam::Address),
+            ("image base", ExceptionParam::Address),
+        ],
+        _ => &[],
     }
+}
 
-    fn update_raw_dump_linux_environ(&mut self, ui: &mut Ui, dump: &Minidump<Mmap>) {
-        let stream = dump.get_raw_stream(MINIDUMP_STREAM_TYPE::LinuxEnviron as u32);
-        if let Err(e) = &stream {
-            ui.label("Failed to read stream");
-            ui.label(e.to_string());
-            return;
+/// Whether we have a decoder for this kind of stream
+fn stream_supported(stream_type: MINIDUMP_STREAM_TYPE) -> bool {
+    use MINIDUMP_STREAM_TYPE::*;
+    matches!(
+        stream_type,
+        SystemInfoStream
+            | MiscInfoStream
+            | ThreadNamesStream
+            | ThreadListStream
+            | ThreadExListStream
+            | AssertionInfoStream
+            | BreakpadInfoStream
+            | CrashpadInfoStream
+            | ExceptionStream
+            | ModuleListStream
+            | UnloadedModuleListStream
+            | MemoryListStream
+            | Memory64ListStream
+            | MemoryInfoListStream
+            | MozMacosCrashInfoStream
+            | LinuxCmdLine
+            | LinuxMaps
+            | LinuxCpuInfo
+            | LinuxEnviron
+            | LinuxLsbRelease
+            | LinuxProcStatus
+    )
+}
+
+/// The product (and version) that the dump says it came from.
+///
+/// Breakpad and Crashpad clients conventionally record this as "prod"/"ver"
+/// annotations, while Mozilla's crash reporter uses "ProductName"/"Version".
+/// These can be on the CrashpadInfo stream itself or on one of its modules.
+fn dump_producer(dump: &Minidump<Mmap>) -> Option<String> {
+    const KEYS: &[(&str, &str)] = &[("prod", "ver"), ("ProductName", "Version")];
+    let crashpad_info = dump.get_stream::<minidump::MinidumpCrashpadInfo>().ok()?;
+    let annotation_sets = std::iter::once(&crashpad_info.simple_annotations).chain(
+        crashpad_info
+            .module_list
+            .iter()
+            .map(|module| &module.simple_annotations),
+    );
+    for annotations in annotation_sets {
+        for (product_key, version_key) in KEYS {
+            if let Some(product) = annotations.get(*product_key) {
+                return Some(match annotations.get(*version_key) {
+                    Some(version) => format!("{product} {version}"),
+                    None => product.clone(),
+                });
+            }
         }
-        let stream = stream.unwrap();
+    }
+    None
+}
+
+fn stream_label(stream_type: u32) -> String {
+    if let Some(stream_type) = MINIDUMP_STREAM_TYPE::from_u32(stream_type) {
+        format!("{stream_type:?}")
+    } else {
+        format!(
+            "<unknown> (0x{stream_type:08x}, {})",
+            crate::stream_vendor(stream_type)
+        )
+    }
+}
+
+/// The stream directory as `index,type name,type hex,vendor,size` lines
+fn stream_directory_csv(dump: &Minidump<Mmap>) -> String {
+    let mut csv = String::from("index,type name,type hex,vendor,size\n");
+    for (i, stream) in dump.all_streams().enumerate() {
+        let name = MINIDUMP_STREAM_TYPE::from_u32(stream.stream_type)
+            .map(|stream_type| format!("{stream_type:?}"))
+            .unwrap_or_else(|| "<unknown>".to_owned());
+        csv.push_str(&format!(
+            "{i},{name},0x{:08x},{},{}\n",
+            stream.stream_type,
+            crate::stream_vendor(stream.stream_type),
+            stream.location.data_size,
+        ));
+    }
+    csv
+}
+
+/// The stream directory as a JSON array, one object per entry
+fn stream_directory_json(dump: &Minidump<Mmap>) -> String {
+    let streams = dump
+        .all_streams()
+        .enumerate()
+        .map(|(i, stream)| {
+            let name = MINIDUMP_STREAM_TYPE::from_u32(stream.stream_type)
+                .map(|stream_type| format!("{stream_type:?}"));
+            serde_json::json!({
+                "index": i,
+                "stream_type": stream.stream_type,
+                "stream_type_name": name,
+                "vendor": crate::stream_vendor(stream.stream_type),
+                "rva": stream.location.rva,
+                "size": stream.location.data_size,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string_pretty(&streams).unwrap_or_default()
+}
+
+/// A stream printed as text, the same way minidump-dump does. A non-brief MemoryList
+/// makes megabytes of it, so it's only printed once it's actually shown or copied.
+struct StreamText<'a, 'd> {
+    dump: &'a Minidump<'d, Mmap>,
+    stream: MINIDUMP_STREAM_TYPE,
+    brief: bool,
+    /// The patterns to hide environment variables with, if they should be hidden
+    redact: Option<Vec<String>>,
+    text: std::cell::OnceCell<Result<String, String>>,
+}
+
+impl StreamText<'_, '_> {
+    fn get(&self) -> Result<&str, &str> {
+        self.text
+            .get_or_init(|| self.print().map_err(|e| e.to_string()))
+            .as_ref()
+            .map(String::as_str)
+            .map_err(String::as_str)
+    }
+
+    fn print(&self) -> Result<String, Box<dyn std::error::Error>> {
+        use MINIDUMP_STREAM_TYPE::*;
+        let (dump, stream, brief) = (self.dump, self.stream, self.brief);
+        let system_info = dump.get_stream::<minidump::MinidumpSystemInfo>();
+        let misc_info = dump.get_stream::<minidump::MinidumpMiscInfo>();
+
         let mut bytes = Vec::new();
-        print_raw_stream("LinuxEnviron", stream, &mut bytes).unwrap();
-        let text = String::from_utf8(bytes).unwrap();
-        ui.monospace(text);
+        match stream {
+            SystemInfoStream => dump
+                .get_stream::<minidump::MinidumpSystemInfo>()?
+                .print(&mut bytes),
+            ThreadNamesStream => dump
+                .get_stream::<minidump::MinidumpThreadNames>()?
+                .print(&mut bytes),
+            MiscInfoStream => dump
+                .get_stream::<minidump::MinidumpMiscInfo>()?
+                .print(&mut bytes),
+            ThreadListStream => {
+                let memory = dump.get_memory();
+                dump.get_stream::<minidump::MinidumpThreadList>()?.print(
+                    &mut bytes,
+                    memory.as_ref(),
+                    system_info.as_ref().ok(),
+                    misc_info.as_ref().ok(),
+                    brief,
+                )
+            }
+            AssertionInfoStream => dump
+                .get_stream::<minidump::MinidumpAssertion>()?
+                .print(&mut bytes),
+            BreakpadInfoStream => dump
+                .get_stream::<minidump::MinidumpBreakpadInfo>()?
+                .print(&mut bytes),
+            CrashpadInfoStream => dump
+                .get_stream::<minidump::MinidumpCrashpadInfo>()?
+                .print(&mut bytes),
+            ExceptionStream => dump.get_stream::<minidump::MinidumpException>()?.print(
+                &mut bytes,
+                system_info.as_ref().ok(),
+                misc_info.as_ref().ok(),
+            ),
+            ModuleListStream => dump
+                .get_stream::<minidump::MinidumpModuleList>()?
+                .print(&mut bytes),
+            UnloadedModuleListStream => dump
+                .get_stream::<minidump::MinidumpUnloadedModuleList>()?
+                .print(&mut bytes),
+            MemoryListStream => dump
+                .get_stream::<minidump::MinidumpMemoryList>()?
+                .print(&mut bytes, brief),
+            // Full-memory dumps can have gigabytes of this, so the bytes are only
+            // ever shown a page at a time by the hex viewer
+            Memory64ListStream => dump
+                .get_stream::<minidump::MinidumpMemory64List>()?
+                .print(&mut bytes, true),
+            MemoryInfoListStream => dump
+                .get_stream::<minidump::MinidumpMemoryInfoList>()?
+                .print(&mut bytes),
+            MozMacosCrashInfoStream => dump
+                .get_stream::<minidump::MinidumpMacCrashInfo>()?
+                .print(&mut bytes),
+            ThreadExListStream => match parse_thread_ex_list(dump.get_raw_stream(stream as u32)?) {
+                Some(threads) => print_thread_ex_list(&threads, &mut bytes),
+                None => {
+                    use std::io::Write;
+                    writeln!(bytes, "MINIDUMP_THREAD_EX_LIST is truncated")
+                }
+            },
+            // Redacted here so that copying the stream doesn't leak anything either
+            LinuxEnviron if self.redact.is_some() => print_raw_stream(
+                &format!("{stream:?}"),
+                &redact_environ(
+                    dump.get_raw_stream(stream as u32)?,
+                    self.redact.as_deref().unwrap_or_default(),
+                ),
+                &mut bytes,
+            ),
+            LinuxMaps | LinuxCmdLine | LinuxCpuInfo | LinuxEnviron | LinuxLsbRelease
+            | LinuxProcStatus => print_raw_stream(
+                &format!("{stream:?}"),
+                dump.get_raw_stream(stream as u32)?,
+                &mut bytes,
+            ),
+            _ => Ok(()),
+        }?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
     }
 }
 
@@ -608,3 +1932,196 @@ fn print_raw_stream<T: std::io::Write>(
         .join("\\0\n");
     write!(out, "{s}\n\n")
 }
+
+/// Parses the `Key:\tvalue` lines of files like /proc/status and /proc/cpuinfo
+fn parse_linux_key_values(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+        .collect()
+}
+
+/// Turns the `1234 kB` values in /proc/status into something readable
+fn format_kb(value: &str) -> Option<String> {
+    let kb = value.strip_suffix(" kB")?.trim().parse::<u64>().ok()?;
+    let units = ["KiB", "MiB", "GiB", "TiB"];
+    let mut size = kb as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    Some(format!("{size:.1} {}", units[unit]))
+}
+
+/// Parses a MINIDUMP_THREAD_EX_LIST, `None` if it's truncated
+fn parse_thread_ex_list(bytes: &[u8]) -> Option<Vec<ThreadEx>> {
+    const THREAD_EX_SIZE: usize = 64;
+    let u32_at = |offset: usize| {
+        Some(u32::from_le_bytes(
+            bytes.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+    let u64_at = |offset: usize| {
+        Some(u64::from_le_bytes(
+            bytes.get(offset..offset + 8)?.try_into().ok()?,
+        ))
+    };
+
+    let count = u32_at(0)? as usize;
+    (0..count)
+        .map(|idx| {
+            let base = 4 + idx * THREAD_EX_SIZE;
+            Some(ThreadEx {
+                thread_id: u32_at(base)?,
+                suspend_count: u32_at(base + 4)?,
+                priority_class: u32_at(base + 8)?,
+                priority: u32_at(base + 12)?,
+                teb: u64_at(base + 16)?,
+                stack_start: u64_at(base + 24)?,
+                stack_size: u32_at(base + 32)?,
+                // (skipping the stack's rva)
+                context_size: u32_at(base + 40)?,
+                // (and the context's rva)
+                backing_store_start: u64_at(base + 48)?,
+                backing_store_size: u32_at(base + 56)?,
+            })
+        })
+        .collect()
+}
+
+fn print_thread_ex_list<T: std::io::Write>(
+    threads: &[ThreadEx],
+    out: &mut T,
+) -> std::io::Result<()> {
+    writeln!(out, "MINIDUMP_THREAD_EX_LIST")?;
+    writeln!(out, "  thread_count = {}", threads.len())?;
+    writeln!(out)?;
+    for (idx, thread) in threads.iter().enumerate() {
+        writeln!(out, "thread[{idx}]")?;
+        writeln!(out, "MINIDUMP_THREAD_EX")?;
+        writeln!(
+            out,
+            "  thread_id                   = {:#x}",
+            thread.thread_id
+        )?;
+        writeln!(
+            out,
+            "  suspend_count               = {}",
+            thread.suspend_count
+        )?;
+        writeln!(
+            out,
+            "  priority_class              = {:#x}",
+            thread.priority_class
+        )?;
+        writeln!(
+            out,
+            "  priority                    = {:#x}",
+            thread.priority
+        )?;
+        writeln!(out, "  teb                         = {:#x}", thread.teb)?;
+        writeln!(
+            out,
+            "  stack.start_of_memory_range = {:#x}",
+            thread.stack_start
+        )?;
+        writeln!(
+            out,
+            "  stack.memory.data_size      = {:#x}",
+            thread.stack_size
+        )?;
+        writeln!(
+            out,
+            "  thread_context.data_size    = {:#x}",
+            thread.context_size
+        )?;
+        writeln!(
+            out,
+            "  backing_store.start         = {:#x}",
+            thread.backing_store_start
+        )?;
+        writeln!(
+            out,
+            "  backing_store.data_size     = {:#x}",
+            thread.backing_store_size
+        )?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn parse_linux_maps_line(line: &str) -> Option<LinuxMapsEntry> {
+    let mut fields = line.splitn(6, char::is_whitespace);
+    let (start, end) = fields.next()?.split_once('-')?;
+    let perms = fields.next()?;
+    let offset = fields.next()?;
+    let device = fields.next()?;
+    let inode = fields.next()?;
+    let path = fields.next().unwrap_or_default().trim();
+    Some(LinuxMapsEntry {
+        start: u64::from_str_radix(start, 16).ok()?,
+        end: u64::from_str_radix(end, 16).ok()?,
+        perms,
+        offset,
+        device,
+        inode,
+        path,
+    })
+}
+
+pub fn region_contains(base_address: u64, size: u64, addr: u64) -> bool {
+    addr >= base_address && addr - base_address < size
+}
+
+/// Shows some minidump-dump style text in a (read-only) text box
+fn raw_text_view(ui: &mut Ui, text: &StreamText) {
+    match text.get() {
+        Ok(mut text) => {
+            ui.add(
+                egui::TextEdit::multiline(&mut text)
+                    .font(TextStyle::Monospace)
+                    .desired_width(f32::INFINITY),
+            );
+        }
+        Err(e) => {
+            ui.label("Failed to read stream");
+            ui.label(e);
+        }
+    }
+}
+
+/// Shows a raw Linux stream's text as is
+fn raw_monospace(ui: &mut Ui, text: &StreamText) {
+    match text.get() {
+        Ok(text) => {
+            ui.monospace(text);
+        }
+        Err(e) => {
+            ui.label("Failed to read stream");
+            ui.label(e);
+        }
+    }
+}
+
+/// Masks the values of the variables in a NUL-separated `NAME=value` environment
+/// whose names contain any of the (uppercase) `patterns`
+fn redact_environ(environ: &[u8], patterns: &[String]) -> Vec<u8> {
+    let vars = environ.split(|&b| b == 0).map(|var| {
+        let Some(eq) = var.iter().position(|&b| b == b'=') else {
+            return var.to_vec();
+        };
+        let name = String::from_utf8_lossy(&var[..eq]).to_uppercase();
+        if patterns
+            .iter()
+            .any(|pattern| name.contains(pattern.as_str()))
+        {
+            let mut redacted = var[..=eq].to_vec();
+            redacted.extend_from_slice(b"<redacted>");
+            redacted
+        } else {
+            var.to_vec()
+        }
+    });
+    vars.collect::<Vec<_>>().join(&0)
+}
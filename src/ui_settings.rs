@@ -1,20 +1,32 @@
 use eframe::egui;
-use egui::Ui;
+use egui::{Color32, Ui};
 
-use crate::processor::ProcessingStatus;
-use crate::MyApp;
+use crate::processor::{ProcessingStatus, SymbolPriority};
+use crate::{MyApp, Tab, Theme};
 
 impl MyApp {
     pub fn ui_settings(&mut self, ui: &mut Ui, ctx: &egui::Context) {
         ui.add_space(20.0);
         ui.heading("choose minidump");
         ui.add_space(10.0);
-        let message = match self.cur_status {
-            ProcessingStatus::NoDump => "Select or drop a minidump!",
-            ProcessingStatus::ReadingDump => "Reading minidump...",
-            ProcessingStatus::RawProcessing => "Processing minidump...",
-            ProcessingStatus::Symbolicating => "Minidump processed!",
-            ProcessingStatus::Done => "Minidump processed!",
+        let message = match self.doc.cur_status {
+            ProcessingStatus::NoDump => "Select or drop a minidump!".to_owned(),
+            ProcessingStatus::ReadingDump => "Reading minidump...".to_owned(),
+            ProcessingStatus::RawProcessing => "Processing minidump...".to_owned(),
+            ProcessingStatus::Symbolicating => "Minidump processed!".to_owned(),
+            ProcessingStatus::Done => "Minidump processed!".to_owned(),
+            ProcessingStatus::Failed => {
+                let reason = match (&self.doc.minidump, &self.doc.processed) {
+                    (Some(Err(e)), _) => e.to_string(),
+                    (_, Some(Err(e))) => e.to_string(),
+                    _ => self
+                        .doc
+                        .processing_error
+                        .clone()
+                        .unwrap_or_else(|| "unknown error".to_owned()),
+                };
+                format!("Processing failed: {reason}")
+            }
         };
 
         // Show a listing of currently known minidumps to inspect
@@ -32,36 +44,87 @@ impl MyApp {
         }
         ui.add_space(10.0);
         ui.horizontal(|ui| {
+            let cancellable = self.doc.is_busy();
+            if cancellable {
+                spinner(ui);
+            }
             ui.label(message);
 
-            let cancellable = match self.cur_status {
-                ProcessingStatus::NoDump | ProcessingStatus::Done => false,
-                ProcessingStatus::ReadingDump
-                | ProcessingStatus::RawProcessing
-                | ProcessingStatus::Symbolicating => true,
-            };
             ui.add_enabled_ui(cancellable, |ui| {
                 if ui.button("❌ cancel").clicked() {
                     self.cancel_processing();
                 }
             });
-            let reprocessable = matches!(&self.minidump, Some(Ok(_)));
+            let symbolicating = self.doc.cur_status == ProcessingStatus::Symbolicating;
+            ui.add_enabled_ui(symbolicating, |ui| {
+                if ui
+                    .button("⏹ stop fetching symbols")
+                    .on_hover_text("finish processing with the symbols that are already on disk")
+                    .clicked()
+                {
+                    self.stop_fetching_symbols();
+                }
+            });
+            let reprocessable = matches!(&self.doc.minidump, Some(Ok(_)));
             ui.add_enabled_ui(reprocessable, |ui| {
-                if ui.button("💫 reprocess").clicked() {
-                    self.process_dump(self.minidump.as_ref().unwrap().as_ref().unwrap().clone());
+                if ui.button("💫 reprocess").on_hover_text("Ctrl+R").clicked() {
+                    self.process_dump(
+                        self.doc
+                            .minidump
+                            .as_ref()
+                            .unwrap()
+                            .as_ref()
+                            .unwrap()
+                            .clone(),
+                    );
                 }
             });
         });
 
         ui.add_space(10.0);
 
-        if ui.button("Open file...").clicked() {
-            if let Some(path) = rfd::FileDialog::new()
-                .add_filter("minidump", &["dmp"])
-                .pick_file()
-            {
-                self.settings.available_paths.push(path);
-                self.set_path(self.settings.available_paths.len() - 1);
+        let busy = self.doc.is_busy();
+        if ui
+            .add_enabled(!busy, egui::Button::new("Open file..."))
+            .on_hover_text("Ctrl+O")
+            .on_disabled_hover_text("wait for the current minidump to finish (or cancel it)")
+            .clicked()
+        {
+            self.open_file_dialog();
+        }
+        if let Some(error) = &self.drop_error {
+            ui.colored_label(Color32::from_rgb(200, 60, 60), error);
+        }
+
+        self.settings.recent_paths.retain(|path| path.exists());
+        if !self.settings.recent_paths.is_empty() {
+            ui.add_space(10.0);
+            ui.label("recent:");
+            let mut reopen = None;
+            for path in &self.settings.recent_paths {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_else(|| path.to_string_lossy());
+                if ui
+                    .link(&*name)
+                    .on_hover_text(path.display().to_string())
+                    .clicked()
+                {
+                    reopen = Some(path.clone());
+                }
+            }
+            if let Some(path) = reopen {
+                let idx = self
+                    .settings
+                    .available_paths
+                    .iter()
+                    .position(|available| *available == path)
+                    .unwrap_or_else(|| {
+                        self.settings.available_paths.push(path);
+                        self.settings.available_paths.len() - 1
+                    });
+                self.set_path(idx);
             }
         }
 
@@ -73,20 +136,67 @@ impl MyApp {
             });
         }
         */
-        ui.add_space(60.0);
+        ui.add_space(10.0);
+        let has_symbol_sources = self
+            .settings
+            .symbol_urls
+            .iter()
+            .filter(|_| !self.settings.offline)
+            .chain(&self.settings.symbol_paths)
+            .any(|(source, enabled)| *enabled && !source.trim().is_empty());
+        if !has_symbol_sources {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    Color32::from_rgb(220, 140, 0),
+                    "⚠ No symbol sources — stacks will not be symbolicated",
+                );
+                if ui.button("restore default servers").clicked() {
+                    self.settings.symbol_urls = crate::default_symbol_urls();
+                }
+            });
+        }
+
+        ui.add_space(50.0);
         ui.separator();
-        ui.heading("symbol servers");
+        ui.heading("symbol servers").on_hover_text(
+            "Redirects are followed, so a server can just be an index that forwards to the real symbol store",
+        );
         ui.add_space(10.0);
+        ui.checkbox(
+            &mut self.settings.offline,
+            "offline (only use local and already downloaded symbols)",
+        );
         let mut to_remove = vec![];
+        let mut to_swap = None;
+        let len = self.settings.symbol_urls.len();
         for (idx, (item, enabled)) in self.settings.symbol_urls.iter_mut().enumerate() {
             ui.horizontal(|ui| {
                 ui.checkbox(enabled, "");
                 ui.text_edit_singleline(item);
+                if let Some(problem) = (!item.trim().is_empty())
+                    .then(|| crate::symbol_url_problem(item))
+                    .flatten()
+                {
+                    ui.colored_label(Color32::RED, "⚠")
+                        .on_hover_text(format!("{problem}; this server will be skipped"));
+                }
+                if ui.add_enabled(idx > 0, egui::Button::new("▲")).clicked() {
+                    to_swap = Some((idx - 1, idx));
+                }
+                if ui
+                    .add_enabled(idx + 1 < len, egui::Button::new("▼"))
+                    .clicked()
+                {
+                    to_swap = Some((idx, idx + 1));
+                }
                 if ui.button("❌").clicked() {
                     to_remove.push(idx);
                 };
             });
         }
+        if let Some((a, b)) = to_swap {
+            self.settings.symbol_urls.swap(a, b);
+        }
         for idx in to_remove.into_iter().rev() {
             self.settings.symbol_urls.remove(idx);
         }
@@ -98,17 +208,78 @@ impl MyApp {
         ui.heading("local symbols");
         ui.add_space(10.0);
         let mut to_remove = vec![];
+        let mut to_swap = None;
+        let len = self.settings.symbol_paths.len();
         for (idx, (item, enabled)) in self.settings.symbol_paths.iter_mut().enumerate() {
             ui.horizontal(|ui| {
                 ui.checkbox(enabled, "");
                 ui.text_edit_singleline(item);
+                if ui.add_enabled(idx > 0, egui::Button::new("▲")).clicked() {
+                    to_swap = Some((idx - 1, idx));
+                }
+                if ui
+                    .add_enabled(idx + 1 < len, egui::Button::new("▼"))
+                    .clicked()
+                {
+                    to_swap = Some((idx, idx + 1));
+                }
                 if ui.button("❌").clicked() {
                     to_remove.push(idx);
                 };
             });
         }
+        for idx in to_remove.into_iter().rev() {
+            self.settings.symbol_paths.remove(idx);
+        }
+        if let Some((a, b)) = to_swap {
+            self.settings.symbol_paths.swap(a, b);
+        }
+        ui.horizontal(|ui| {
+            if ui.button("➕").clicked() {
+                self.settings.symbol_paths.push((String::new(), true));
+            }
+            if ui
+                .button("add symbol zip...")
+                .on_hover_text("a .zip of breakpad symbols, extracted while processing")
+                .clicked()
+            {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("symbol archive", &["zip"])
+                    .pick_file()
+                {
+                    self.settings
+                        .symbol_paths
+                        .push((path.display().to_string(), true));
+                }
+            }
+        });
+
+        ui.add_space(20.0);
+        ui.heading("symbol overrides");
+        ui.add_space(10.0);
+        ui.label("local .sym files to use for a module, by its debug id");
+        let mut overrides_to_remove = vec![];
+        for (idx, (debug_id, path)) in self.settings.symbol_overrides.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(debug_id).hint_text("debug id"));
+                ui.add(egui::TextEdit::singleline(path).hint_text("symbol file"));
+                if ui.button("📂").on_hover_text("pick the file").clicked() {
+                    if let Some(picked) = pick_symbol_override() {
+                        *path = picked;
+                    }
+                }
+                if ui.button("❌").clicked() {
+                    overrides_to_remove.push(idx);
+                };
+            });
+        }
+        for idx in overrides_to_remove.into_iter().rev() {
+            self.settings.symbol_overrides.remove(idx);
+        }
         if ui.button("➕").clicked() {
-            self.settings.symbol_paths.push((String::new(), true));
+            self.settings
+                .symbol_overrides
+                .push((String::new(), String::new()));
         }
 
         ui.add_space(20.0);
@@ -123,26 +294,143 @@ impl MyApp {
             ui.label("http timeout secs");
             ui.text_edit_singleline(&mut self.settings.http_timeout_secs);
         });
-        for idx in to_remove.into_iter().rev() {
-            self.settings.symbol_paths.remove(idx);
-        }
+        ui.horizontal(|ui| {
+            ui.label("max symbol file MB");
+            ui.text_edit_singleline(&mut self.settings.max_symbol_size_mb)
+                .on_hover_text("leave empty for no limit");
+        });
+        ui.horizontal(|ui| {
+            ui.label("symbol download retries");
+            ui.text_edit_singleline(&mut self.settings.symbol_retries)
+                .on_hover_text("leave empty to never retry. Modules without symbols also get retried, so this slows processing down");
+        });
+        ui.horizontal(|ui| {
+            ui.label("symbol download threads");
+            ui.text_edit_singleline(&mut self.settings.download_threads)
+                .on_hover_text("leave empty to download on the processing thread");
+        });
+        ui.checkbox(
+            &mut self.settings.reuse_results,
+            "cache results for reopening with the same settings",
+        )
+        .on_hover_text("saved with the symbol cache, and redone when local symbol files change");
+        ui.horizontal(|ui| {
+            ui.label("symbol priority");
+            ui.radio_value(
+                &mut self.settings.symbol_priority,
+                SymbolPriority::LocalFirst,
+                "local first",
+            );
+            ui.radio_value(
+                &mut self.settings.symbol_priority,
+                SymbolPriority::ServerFirst,
+                "server first",
+            )
+            .on_hover_text("only search the local symbol paths for symbols the servers don't have");
+        });
         ui.checkbox(
             &mut self.settings.raw_dump_brief,
             "hide memory dumps in raw mode",
         );
+        ui.horizontal(|ui| {
+            ui.label("secret environment variables");
+            ui.text_edit_singleline(&mut self.settings.sensitive_env_patterns)
+                .on_hover_text("comma-separated, any variable whose name contains one of these has its value hidden");
+        });
+        ui.checkbox(
+            &mut self.settings.skip_symbols,
+            "skip symbols (fast, unsymbolicated stackwalk)",
+        );
+        ui.horizontal(|ui| {
+            ui.label("max frames per thread");
+            ui.text_edit_singleline(&mut self.settings.max_unwind_frames)
+                .on_hover_text("leave empty for no limit (useful for stack overflows)");
+        });
+        ui.checkbox(
+            &mut self.settings.allow_stack_scan,
+            "allow stack scanning (may produce bogus frames)",
+        );
+        ui.horizontal(|ui| {
+            ui.label("source root");
+            ui.text_edit_singleline(&mut self.settings.source_root)
+                .on_hover_text(
+                    "a checkout of the crashing program's source, to open source locations in",
+                );
+            if ui.button("browse...").clicked() {
+                if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                    self.settings.source_root = path.display().to_string();
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("editor url");
+            ui.text_edit_singleline(&mut self.settings.editor_url)
+                .on_hover_text(
+                "e.g. vscode://file/{path}:{line}\nleave empty to open files with the default app",
+            );
+        });
+        ui.checkbox(
+            &mut self.settings.focus_crash_on_load,
+            "select the crashing frame when a dump loads",
+        );
+        ui.horizontal(|ui| {
+            ui.label("default tab");
+            egui::ComboBox::from_id_source("default tab")
+                .selected_text(format!("{:?}", self.settings.default_tab))
+                .show_ui(ui, |ui| {
+                    for tab in [
+                        Tab::Settings,
+                        Tab::RawDump,
+                        Tab::Processed,
+                        Tab::Logs,
+                        Tab::Compare,
+                    ] {
+                        ui.selectable_value(
+                            &mut self.settings.default_tab,
+                            tab,
+                            format!("{tab:?}"),
+                        );
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label("theme");
+            egui::ComboBox::from_id_source("theme")
+                .selected_text(format!("{:?}", self.settings.theme))
+                .show_ui(ui, |ui| {
+                    for theme in [Theme::Dark, Theme::Light, Theme::FollowSystem] {
+                        ui.selectable_value(&mut self.settings.theme, theme, format!("{theme:?}"));
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label("ui scale");
+            ui.add(egui::Slider::new(&mut self.settings.ui_scale, 0.75..=2.0).step_by(0.05));
+            if ui.button("reset").clicked() {
+                self.settings.ui_scale = 1.0;
+            }
+        });
 
         ui.add_space(20.0);
         preview_files_being_dropped(ctx);
 
-        // Collect dropped files:
-        let mut pushed_path = false;
-        for file in &ctx.input().raw.dropped_files {
-            if let Some(path) = &file.path {
-                pushed_path = true;
-                self.settings.available_paths.push(path.clone());
+        // Collect dropped files, each one gets opened as its own document:
+        let dropped_paths = ctx
+            .input()
+            .raw
+            .dropped_files
+            .iter()
+            .filter_map(|file| file.path.clone())
+            .collect::<Vec<_>>();
+        for path in dropped_paths {
+            // Catch things like a screenshot being dropped by accident before making a
+            // whole document for them
+            if let Some(problem) = crate::processor::not_a_minidump(&path) {
+                self.drop_error = Some(format!("{}: {problem}", path.display()));
+                continue;
             }
-        }
-        if pushed_path {
+            self.drop_error = None;
+            self.settings.available_paths.push(path);
             self.set_path(self.settings.available_paths.len() - 1);
         }
     }
@@ -179,3 +467,34 @@ fn preview_files_being_dropped(ctx: &egui::Context) {
         );
     }
 }
+
+/// Asks for a symbol file to override a module's symbols with
+pub(crate) fn pick_symbol_override() -> Option<String> {
+    rfd::FileDialog::new()
+        .add_filter("breakpad symbols", &["sym"])
+        .pick_file()
+        .map(|path| path.display().to_string())
+}
+
+/// A little spinning arc to show that something is happening
+fn spinner(ui: &mut Ui) {
+    let size = ui.style().spacing.interact_size.y;
+    let (rect, _response) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    if ui.is_rect_visible(rect) {
+        ui.ctx().request_repaint();
+        let radius = rect.height() / 2.0 - 2.0;
+        let time = ui.input().time;
+        let start_angle = time * std::f64::consts::TAU;
+        let end_angle = start_angle + 240f64.to_radians() * time.sin();
+        let points = (0..20)
+            .map(|i| {
+                let angle = egui::lerp(start_angle..=end_angle, i as f64 / 20.0);
+                let (sin, cos) = angle.sin_cos();
+                rect.center() + radius * egui::vec2(cos as f32, sin as f32)
+            })
+            .collect();
+        let color = ui.visuals().strong_text_color();
+        ui.painter()
+            .add(egui::Shape::line(points, egui::Stroke::new(3.0, color)));
+    }
+}